@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use tightvec::UndoTightVec;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Device {
     name: String,
@@ -24,41 +28,52 @@ fn main() {
 }
 
 fn part_1(devices: &[Device]) {
-    let current_device = devices.iter().find(|x| x.name == "you").unwrap();
-
-    let res = find_paths(current_device, devices, &mut Vec::new());
+    let positions: HashMap<&str, usize> = devices
+        .iter()
+        .enumerate()
+        .map(|(index, device)| (device.name.as_str(), index))
+        .collect();
+
+    let res = find_paths(
+        positions["you"],
+        devices,
+        &positions,
+        &mut UndoTightVec::new(devices.len()),
+    );
 
     println!("Found {res} paths");
 }
 
 fn find_paths(
-    current_device: &Device,
+    current_device: usize,
     devices: &[Device],
-    visited_devices: &mut Vec<Device>,
+    positions: &HashMap<&str, usize>,
+    visited_devices: &mut UndoTightVec,
 ) -> usize {
-    if visited_devices.contains(current_device) {
+    if visited_devices.index(current_device) {
         return 0;
     }
 
-    visited_devices.push(current_device.clone());
+    let checkpoint = visited_devices.checkpoint();
 
-    let devices_len = visited_devices.len();
+    visited_devices.set(current_device, true);
 
     let mut counter = 0;
-    for device in &current_device.outputs {
+    for device in &devices[current_device].outputs {
         if device == "out" {
             counter += 1;
             continue;
         }
 
         counter += find_paths(
-            devices.iter().find(|x| x.name == *device).unwrap(),
+            positions[device.as_str()],
             devices,
+            positions,
             visited_devices,
         );
-
-        visited_devices.truncate(devices_len);
     }
 
+    visited_devices.rollback(checkpoint);
+
     counter
 }
@@ -1,6 +1,6 @@
 use std::thread;
 
-use tightvec::TightVec;
+use tightvec::{BitContainer, BitGrid, BitRows, Connectivity};
 
 const MAX_THREADS: usize = 24;
 
@@ -81,32 +81,24 @@ fn calculate_field_size(tiles: &[Coordinate]) -> (usize, usize) {
     (height, width)
 }
 
-fn allocate_field(height: usize, width: usize) -> Vec<TightVec> {
+fn allocate_field(height: usize, width: usize) -> BitGrid {
     println!("Allocating field");
-    let mut field = Vec::with_capacity(height);
-
-    let line = TightVec::with_len_and_value(width, false);
-    for _ in 0..height {
-        field.push(line.clone());
-    }
-    field
+    BitGrid::new(height, width)
 }
 
-#[allow(clippy::needless_range_loop)] // Range loops are easier to read for me than iter() + skip()
-fn insert_red_and_connecting_tiles(red_tiles: &[Coordinate], field: &mut [TightVec]) {
+fn insert_red_and_connecting_tiles(red_tiles: &[Coordinate], field: &mut BitGrid) {
     println!("Inserting red tiles and connecting tiles");
 
     let mut last_tile: Option<Coordinate> = None;
     for current_tile in red_tiles {
         if let Some(last_tile) = last_tile {
-            for row in last_tile.row.min(current_tile.row)..=last_tile.row.max(current_tile.row) {
-                for col in last_tile.col.min(current_tile.col)..=last_tile.col.max(current_tile.col)
-                {
-                    field[row].set(col, true);
-                }
-            }
+            field.fill_rect(
+                last_tile.row.min(current_tile.row)..=last_tile.row.max(current_tile.row),
+                last_tile.col.min(current_tile.col)..=last_tile.col.max(current_tile.col),
+                true,
+            );
         } else {
-            field[current_tile.row].set(current_tile.col, true);
+            field.set(current_tile.row, current_tile.col, true);
         }
         last_tile = Some(*current_tile);
     }
@@ -115,11 +107,11 @@ fn insert_red_and_connecting_tiles(red_tiles: &[Coordinate], field: &mut [TightV
     let first_tile = red_tiles[0];
     let last_tile = last_tile.unwrap();
 
-    for row in last_tile.row.min(first_tile.row)..=last_tile.row.max(first_tile.row) {
-        for col in last_tile.col.min(first_tile.col)..=last_tile.col.max(first_tile.col) {
-            field[row].set(col, true);
-        }
-    }
+    field.fill_rect(
+        last_tile.row.min(first_tile.row)..=last_tile.row.max(first_tile.row),
+        last_tile.col.min(first_tile.col)..=last_tile.col.max(first_tile.col),
+        true,
+    );
 }
 
 fn calculate_possible_rectangles(mut red_tiles: Vec<Coordinate>) -> Vec<Rectangle> {
@@ -150,7 +142,7 @@ fn calculate_possible_rectangles(mut red_tiles: Vec<Coordinate>) -> Vec<Rectangl
     rects
 }
 
-fn check_rectangles(field: &[TightVec], mut rects: Vec<Rectangle>) {
+fn check_rectangles(field: &BitGrid, mut rects: Vec<Rectangle>) {
     println!("Checking rectangles");
 
     let mut biggest_rect_found = 0;
@@ -204,66 +196,18 @@ fn check_rectangles(field: &[TightVec], mut rects: Vec<Rectangle>) {
     });
 }
 
-#[allow(clippy::needless_range_loop)] // Range loops are easier to read for me than iter() + skip()
-fn fill_outlined_shape(field: &mut [TightVec]) {
+fn fill_outlined_shape(field: &mut BitGrid) {
     println!("Filling area");
 
-    for row_index in 0..field.len() {
-        let mut fill = false;
-        let mut continuuous_section = false;
-        let mut uninterrupted_start = 0;
-
-        for col_index in 0..field[0].len() {
-            if !fill && !field[row_index].index(col_index) {
-                continue;
-            } else if fill && !field[row_index].index(col_index) {
-                if continuuous_section {
-                    if row_index != 0 && field[row_index - 1].index(col_index) {
-                        continuuous_section = false;
-                    } else {
-                        field[row_index].fill_multiple(uninterrupted_start, col_index - 1, true);
-                    }
-                }
-            } else if fill && field[row_index].index(col_index) {
-                if !continuuous_section {
-                    field[row_index].fill_multiple(uninterrupted_start, col_index - 1, true);
-                    fill = false
-                }
-            } else if !fill && field[row_index].index(col_index) {
-                uninterrupted_start = col_index;
-                fill = true;
-                continuuous_section = true;
-            }
-        }
-    }
+    field.fill_enclosed(Connectivity::Four);
 }
 
-#[allow(clippy::needless_range_loop)] // readability
-fn check_rectangle(field: &[TightVec], rect: Rectangle) -> Option<usize> {
-    const REGISTER_WIDTH: usize = 64;
-
-    for row in rect.upper_left.row..=rect.lower_right.row {
-        let mut current = rect.upper_left.col;
-
-        while !current.is_multiple_of(REGISTER_WIDTH) && current < rect.lower_right.col {
-            if !field[row].index(current) {
-                return None;
-            }
-            current += 1;
-        }
-
-        while rect.lower_right.col - current > REGISTER_WIDTH {
-            if field[row].get_raw()[current / 64] != u64::MAX {
-                return None;
-            }
-            current += REGISTER_WIDTH;
-        }
-
-        for col in current..=rect.lower_right.col {
-            if !field[row].index(col) {
-                return None;
-            }
-        }
-    }
-    Some(rect.area)
+fn check_rectangle<R: BitRows + ?Sized>(field: &R, rect: Rectangle) -> Option<usize> {
+    (rect.upper_left.row..=rect.lower_right.row)
+        .all(|row| {
+            field
+                .row(row)
+                .all_in(rect.upper_left.col..=rect.lower_right.col)
+        })
+        .then_some(rect.area)
 }
@@ -0,0 +1,78 @@
+//! Compares the reductions with AVX2 kernels (see `src/simd.rs`) with a plain loop over one
+//! storage word at a time, on a vector the size of a day9 field row block. Run with `cargo bench -p tightvec`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tightvec::TightVec;
+
+const LEN: usize = 1 << 24;
+const ROUNDS: u32 = 50;
+
+fn field() -> TightVec {
+    let mut v = TightVec::with_len_and_value(LEN, true);
+    v.set(LEN - 3, false);
+    v
+}
+
+/// average duration of one call of `f`
+fn time<T>(mut f: impl FnMut() -> T) -> Duration {
+    black_box(f());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+
+    start.elapsed() / ROUNDS
+}
+
+fn compare<T>(name: &str, bulk: impl FnMut() -> T, word_by_word: impl FnMut() -> T) {
+    let bulk = time(bulk);
+    let word_by_word = time(word_by_word);
+
+    println!(
+        "{name:<10} bulk {bulk:>10.2?}   word by word {word_by_word:>10.2?}   speedup {:.1}x",
+        word_by_word.as_secs_f64() / bulk.as_secs_f64()
+    );
+}
+
+fn main() {
+    let a = field();
+    let empty = {
+        let mut v: TightVec = TightVec::with_len_and_value(LEN, false);
+        v.set(LEN - 3, true);
+        v
+    };
+
+    compare(
+        "count",
+        || a.as_bitslice().count_ones(),
+        || {
+            black_box(a.get_raw())
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>()
+        },
+    );
+
+    compare(
+        "all set",
+        || a.slice(5..LEN - 4).all(),
+        || {
+            let words = black_box(a.get_raw());
+            words[1..words.len() - 1]
+                .iter()
+                .all(|&word| word == u64::MAX)
+        },
+    );
+
+    compare(
+        "any set",
+        || empty.slice(5..LEN - 4).any(),
+        || {
+            let words = black_box(empty.get_raw());
+            words[1..words.len() - 1].iter().any(|&word| word != 0)
+        },
+    );
+}
@@ -0,0 +1,340 @@
+use alloc::boxed::Box;
+use core::fmt::{self, Debug, Formatter};
+use core::ops::RangeBounds;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::{AtomicU8, AtomicU16, AtomicU32, Ordering};
+
+use crate::error::or_panic;
+use crate::slice::{check_index, try_resolve_range};
+use crate::{TightVec, TightVecError, Word};
+
+/// A [`Word`] with a matching atomic integer type, i.e. every word except `u128`.
+/// `u64` is only one on targets with 64-bit atomics.
+pub trait AtomicWord: Word {
+    type Atomic: Send + Sync;
+
+    fn new_atomic(value: Self) -> Self::Atomic;
+
+    fn into_inner(atomic: Self::Atomic) -> Self;
+
+    fn load(atomic: &Self::Atomic, ordering: Ordering) -> Self;
+
+    fn fetch_or(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self;
+
+    fn fetch_and(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self;
+}
+
+macro_rules! impl_atomic_word {
+    ($($t:ty => $atomic:ty),*) => {$(
+        impl AtomicWord for $t {
+            type Atomic = $atomic;
+
+            fn new_atomic(value: Self) -> $atomic {
+                <$atomic>::new(value)
+            }
+
+            fn into_inner(atomic: $atomic) -> Self {
+                atomic.into_inner()
+            }
+
+            fn load(atomic: &$atomic, ordering: Ordering) -> Self {
+                atomic.load(ordering)
+            }
+
+            fn fetch_or(atomic: &$atomic, value: Self, ordering: Ordering) -> Self {
+                atomic.fetch_or(value, ordering)
+            }
+
+            fn fetch_and(atomic: &$atomic, value: Self, ordering: Ordering) -> Self {
+                atomic.fetch_and(value, ordering)
+            }
+        }
+    )*};
+}
+
+impl_atomic_word!(u8 => AtomicU8, u16 => AtomicU16, u32 => AtomicU32);
+#[cfg(target_has_atomic = "64")]
+impl_atomic_word!(u64 => AtomicU64);
+
+/// the default word of [`AtomicTightVec`], the default storage word where it has an atomic type
+#[cfg(target_has_atomic = "64")]
+type AtomicStorageItem = crate::StorageItem;
+#[cfg(not(target_has_atomic = "64"))]
+type AtomicStorageItem = u32;
+
+/// A fixed-length bit vector whose entries can be written through a shared reference, so several
+/// threads can set bits of the same vector without a lock.
+///
+/// Every write is a single atomic read-modify-write of the word holding the bit, so concurrent
+/// writes to different bits of the same word never get lost. Writes use `AcqRel` and reads use
+/// `Acquire` ordering. Range operations are atomic per word, not for the range as a whole.
+pub struct AtomicTightVec<W: AtomicWord = AtomicStorageItem> {
+    words: Box<[W::Atomic]>,
+    len: usize,
+}
+
+impl<W: AtomicWord> AtomicTightVec<W> {
+    /// Creates a vector of `len` unset entries
+    pub fn new(len: usize) -> Self {
+        Self::with_len_and_value(len, false)
+    }
+
+    pub fn with_len_and_value(len: usize, value: bool) -> Self {
+        TightVec::with_len_and_value(len, value).into()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn load(&self, index: usize) -> bool {
+        or_panic(self.try_load(index))
+    }
+
+    pub fn try_load(&self, index: usize) -> Result<bool, TightVecError> {
+        check_index(index, self.len)?;
+
+        let mask = W::ONE << (index % W::BITS);
+
+        Ok(W::load(&self.words[index / W::BITS], Ordering::Acquire) & mask != W::ZERO)
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn set(&self, index: usize, value: bool) {
+        or_panic(self.try_set(index, value));
+    }
+
+    pub fn try_set(&self, index: usize, value: bool) -> Result<(), TightVecError> {
+        if value {
+            self.try_fetch_set(index)?;
+        } else {
+            self.try_fetch_clear(index)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the entry and returns its previous value. Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn fetch_set(&self, index: usize) -> bool {
+        or_panic(self.try_fetch_set(index))
+    }
+
+    pub fn try_fetch_set(&self, index: usize) -> Result<bool, TightVecError> {
+        check_index(index, self.len)?;
+
+        let mask = W::ONE << (index % W::BITS);
+        let previous = W::fetch_or(&self.words[index / W::BITS], mask, Ordering::AcqRel);
+
+        Ok(previous & mask != W::ZERO)
+    }
+
+    /// Unsets the entry and returns its previous value. Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn fetch_clear(&self, index: usize) -> bool {
+        or_panic(self.try_fetch_clear(index))
+    }
+
+    pub fn try_fetch_clear(&self, index: usize) -> Result<bool, TightVecError> {
+        check_index(index, self.len)?;
+
+        let mask = W::ONE << (index % W::BITS);
+        let previous = W::fetch_and(&self.words[index / W::BITS], !mask, Ordering::AcqRel);
+
+        Ok(previous & mask != W::ZERO)
+    }
+
+    /// Sets every entry in the range to `value`, one atomic operation per word.
+    /// Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn fill(&self, range: impl RangeBounds<usize>, value: bool) {
+        or_panic(self.try_fill(range, value))
+    }
+
+    pub fn try_fill(
+        &self,
+        range: impl RangeBounds<usize>,
+        value: bool,
+    ) -> Result<(), TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        if start == end {
+            return Ok(());
+        }
+
+        for word_index in start / W::BITS..end.div_ceil(W::BITS) {
+            let word_start = word_index * W::BITS;
+            let mask = W::low_mask((end - word_start).min(W::BITS))
+                & !W::low_mask(start.saturating_sub(word_start));
+
+            if value {
+                W::fetch_or(&self.words[word_index], mask, Ordering::AcqRel);
+            } else {
+                W::fetch_and(&self.words[word_index], !mask, Ordering::AcqRel);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Number of set entries. Only exact while no other thread writes.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| W::load(word, Ordering::Acquire).count_ones() as usize)
+            .sum()
+    }
+
+    /// Copies the current entries into a plain vector. Words written concurrently may be seen
+    /// before or after the write.
+    pub fn to_tightvec(&self) -> TightVec<W> {
+        TightVec {
+            inner: self
+                .words
+                .iter()
+                .map(|word| W::load(word, Ordering::Acquire))
+                .collect(),
+            len: self.len,
+        }
+    }
+
+    /// Converts back into a plain vector once no other thread holds a reference
+    pub fn into_tightvec(self) -> TightVec<W> {
+        TightVec {
+            inner: self.words.into_iter().map(W::into_inner).collect(),
+            len: self.len,
+        }
+    }
+}
+
+impl<W: AtomicWord> From<TightVec<W>> for AtomicTightVec<W> {
+    fn from(v: TightVec<W>) -> Self {
+        Self {
+            words: v.inner.into_iter().map(W::new_atomic).collect(),
+            len: v.len,
+        }
+    }
+}
+
+impl<W: AtomicWord> From<AtomicTightVec<W>> for TightVec<W> {
+    fn from(v: AtomicTightVec<W>) -> Self {
+        v.into_tightvec()
+    }
+}
+
+impl<W: AtomicWord> Default for AtomicTightVec<W> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<W: AtomicWord> Debug for AtomicTightVec<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicTightVec")
+            .field("len", &self.len)
+            .field("bits", &format_args!("{}", self.to_tightvec()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use crate::{AtomicTightVec, TightVec, TightVecError};
+
+    #[test]
+    fn single_threaded() {
+        let v: AtomicTightVec<u8> = AtomicTightVec::new(20);
+
+        assert!(!v.fetch_set(3));
+        assert!(v.fetch_set(3));
+        v.set(19, true);
+        assert!(v.load(19));
+        assert!(v.fetch_clear(19));
+        assert!(!v.load(19));
+
+        v.fill(5..=17, true);
+        v.fill(8..10, false);
+
+        assert_eq!(
+            format!("{v:?}"),
+            "AtomicTightVec { len: 20, bits: 00010111001111111100 }"
+        );
+        assert_eq!(v.count_ones(), 12);
+        assert_eq!(
+            v.try_load(20),
+            Err(TightVecError::IndexOutOfBounds { index: 20, len: 20 })
+        );
+        assert!(v.try_fill(..21, true).is_err());
+    }
+
+    #[test]
+    fn round_trips_tightvec() {
+        let v: TightVec = "1011_0000_1111_0001_1".parse().unwrap();
+
+        let atomic = AtomicTightVec::from(v.clone());
+        assert_eq!(atomic.to_tightvec(), v);
+        assert_eq!(TightVec::from(atomic), v);
+    }
+
+    #[test]
+    fn threads_share_words() {
+        let v: AtomicTightVec = AtomicTightVec::new(1000);
+
+        // every thread writes every fourth bit, so all threads hit every word
+        thread::scope(|scope| {
+            for thread in 0..4 {
+                let v = &v;
+                scope.spawn(move || {
+                    for index in (thread..1000).step_by(4) {
+                        v.set(index, true);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(v.count_ones(), 1000);
+
+        // overlapping ranges from several threads
+        thread::scope(|scope| {
+            for thread in 0..4 {
+                let v = &v;
+                scope.spawn(move || v.fill(thread * 200..thread * 200 + 300, false));
+            }
+        });
+
+        let v = v.into_tightvec();
+        assert_eq!(v.as_bitslice().count_ones(), 100);
+        assert!(v.slice(900..).all());
+    }
+
+    #[test]
+    fn fetch_set_claims_each_bit_once() {
+        let v: AtomicTightVec<u32> = AtomicTightVec::new(500);
+
+        let claimed: usize = thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    let v = &v;
+                    scope.spawn(move || (0..500).filter(|&index| !v.fetch_set(index)).count())
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .sum()
+        });
+
+        assert_eq!(claimed, 500);
+    }
+}
@@ -0,0 +1,387 @@
+//! Generations of two-dimensional cellular automata on a [`BitGrid`], such as Conway's Game of Life.
+//!
+//! Neighbours are counted a word at a time: every cell's count is held in four bit planes, one
+//! word per plane, and each of the eight neighbour words (the rows above, at and below, shifted
+//! one column either way) is added with a ripple of half adders. The rule is then applied to whole
+//! words by comparing the planes against every count it accepts.
+
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+
+use crate::{BitGrid, TightVecError, Word};
+
+/// A birth/survival rule: a dead cell comes alive if its number of live neighbours is one of the
+/// birth counts, a live cell stays alive if it's one of the survival counts.
+///
+/// Parses from and displays as the usual notation, e.g. `B3/S23` for the Game of Life.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// bit `n` is set if `n` neighbours give birth
+    birth: u16,
+    /// bit `n` is set if a cell with `n` neighbours survives
+    survival: u16,
+}
+
+/// What lies beyond the edges of the grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeMode {
+    /// Cells outside the grid are dead
+    Bounded,
+    /// The grid wraps around in both directions, so the last column neighbours the first
+    Toroidal,
+}
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`
+    pub const LIFE: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+    };
+
+    /// Creates a rule from its birth and survival counts. Panics if a count is above 8.
+    #[track_caller]
+    pub fn new(
+        birth: impl IntoIterator<Item = u8>,
+        survival: impl IntoIterator<Item = u8>,
+    ) -> Self {
+        Self {
+            birth: count_mask(birth),
+            survival: count_mask(survival),
+        }
+    }
+
+    /// Whether a dead cell with `neighbours` live neighbours comes alive. Never for more than 8.
+    pub fn is_born(&self, neighbours: u8) -> bool {
+        neighbours <= 8 && self.birth >> neighbours & 1 != 0
+    }
+
+    /// Whether a live cell with `neighbours` live neighbours stays alive. Never for more than 8.
+    pub fn survives(&self, neighbours: u8) -> bool {
+        neighbours <= 8 && self.survival >> neighbours & 1 != 0
+    }
+}
+
+#[track_caller]
+fn count_mask(counts: impl IntoIterator<Item = u8>) -> u16 {
+    counts.into_iter().fold(0, |mask, count| {
+        assert!(count <= 8, "a cell has at most 8 neighbours, got {count}");
+        mask | 1 << count
+    })
+}
+
+/// Accepts `B` and `S` followed by the counts, separated by `/`, in either case and order
+impl FromStr for Rule {
+    type Err = TightVecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rule = Rule {
+            birth: 0,
+            survival: 0,
+        };
+        let mut counts = None;
+
+        for (position, character) in s.chars().enumerate() {
+            match (character, &mut counts) {
+                ('B' | 'b', _) => counts = Some(&mut rule.birth),
+                ('S' | 's', _) => counts = Some(&mut rule.survival),
+                ('/', _) => counts = None,
+                ('0'..='8', Some(counts)) => **counts |= 1 << (character as u8 - b'0'),
+                _ => {
+                    return Err(TightVecError::InvalidCharacter {
+                        character,
+                        position,
+                    });
+                }
+            }
+        }
+
+        Ok(rule)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("B")?;
+        for count in (0..=8).filter(|&count| self.is_born(count)) {
+            write!(f, "{count}")?;
+        }
+
+        f.write_str("/S")?;
+        for count in (0..=8).filter(|&count| self.survives(count)) {
+            write!(f, "{count}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Word> BitGrid<W> {
+    /// Computes the next generation under `rule`, treating the edges according to `edges`
+    pub fn step(&self, rule: &Rule, edges: EdgeMode) -> BitGrid<W> {
+        let (rows, cols) = (self.rows(), self.cols());
+        let words_per_row = self.words_per_row();
+        let mut next = BitGrid::new(rows, cols);
+
+        if rows == 0 || cols == 0 {
+            return next;
+        }
+
+        let words = self.get_raw();
+        let row_words = |row: usize| &words[row * words_per_row..(row + 1) * words_per_row];
+        let wraps = edges == EdgeMode::Toroidal;
+        let last_word_mask = W::low_mask(cols - (words_per_row - 1) * W::BITS);
+        let out = next.words_mut();
+
+        for row in 0..rows {
+            let above = match row {
+                0 => wraps.then_some(rows - 1),
+                _ => Some(row - 1),
+            };
+            let below = match row + 1 {
+                next if next < rows => Some(next),
+                _ => wraps.then_some(0),
+            };
+
+            for word_index in 0..words_per_row {
+                let mut counts = NeighbourCounts::default();
+
+                for (neighbour, is_own_row) in [(above, false), (Some(row), true), (below, false)] {
+                    let Some(neighbour) = neighbour else {
+                        continue;
+                    };
+
+                    let (west, centre, east) =
+                        shifted(row_words(neighbour), word_index, cols, wraps);
+
+                    counts.add(west);
+                    counts.add(east);
+                    if !is_own_row {
+                        counts.add(centre);
+                    }
+                }
+
+                let alive = row_words(row)[word_index];
+                let mut word = (alive & counts.matching(rule.survival))
+                    | (!alive & counts.matching(rule.birth));
+
+                if word_index == words_per_row - 1 {
+                    word &= last_word_mask;
+                }
+
+                out[row * words_per_row + word_index] = word;
+            }
+        }
+
+        next
+    }
+}
+
+/// word `word_index` of a row, along with the same cells' west and east neighbours
+fn shifted<W: Word>(words: &[W], word_index: usize, cols: usize, wraps: bool) -> (W, W, W) {
+    let last = words.len() - 1;
+    let word = words[word_index];
+
+    let west_carry = match word_index {
+        0 if wraps => (words[last] >> ((cols - 1) % W::BITS)) & W::ONE,
+        0 => W::ZERO,
+        _ => words[word_index - 1] >> (W::BITS - 1),
+    };
+
+    // the last column sits inside the last word if the row doesn't fill it
+    let east_carry = match word_index {
+        index if index == last && wraps => (words[0] & W::ONE) << ((cols - 1) % W::BITS),
+        index if index == last => W::ZERO,
+        _ => (words[word_index + 1] & W::ONE) << (W::BITS - 1),
+    };
+
+    ((word << 1) | west_carry, word, (word >> 1) | east_carry)
+}
+
+/// per-cell neighbour counts of one word, bit-sliced: plane `i` holds bit `i` of every count
+#[derive(Default)]
+struct NeighbourCounts<W: Word> {
+    planes: [W; 4],
+}
+
+impl<W: Word> NeighbourCounts<W> {
+    /// adds one to the count of every cell whose bit is set in `neighbours`
+    fn add(&mut self, mut neighbours: W) {
+        for plane in &mut self.planes {
+            let carry = *plane & neighbours;
+            *plane ^= neighbours;
+            neighbours = carry;
+        }
+    }
+
+    /// the cells whose count is one of `counts`, given as a mask like in [`Rule`]
+    fn matching(&self, counts: u16) -> W {
+        (0..=8)
+            .filter(|count| counts >> count & 1 != 0)
+            .fold(W::ZERO, |cells, count| cells | self.equal_to(count))
+    }
+
+    fn equal_to(&self, count: usize) -> W {
+        self.planes
+            .iter()
+            .enumerate()
+            .fold(W::MAX, |cells, (bit, &plane)| match count >> bit & 1 {
+                1 => cells & plane,
+                _ => cells & !plane,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::random;
+    use crate::{BitGrid, EdgeMode, Rule, TightVecError, Word};
+
+    /// one generation, cell by cell
+    fn naive<W: Word>(grid: &BitGrid<W>, rule: &Rule, edges: EdgeMode) -> BitGrid<W> {
+        let (rows, cols) = (grid.rows() as isize, grid.cols() as isize);
+        let mut next = BitGrid::new(grid.rows(), grid.cols());
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut neighbours = 0;
+
+                for (dr, dc) in [
+                    (-1, -1),
+                    (-1, 0),
+                    (-1, 1),
+                    (0, -1),
+                    (0, 1),
+                    (1, -1),
+                    (1, 0),
+                    (1, 1),
+                ] {
+                    let (r, c) = match edges {
+                        EdgeMode::Bounded => (row + dr, col + dc),
+                        EdgeMode::Toroidal => {
+                            ((row + dr).rem_euclid(rows), (col + dc).rem_euclid(cols))
+                        }
+                    };
+
+                    if (0..rows).contains(&r)
+                        && (0..cols).contains(&c)
+                        && grid.get(r as usize, c as usize)
+                    {
+                        neighbours += 1;
+                    }
+                }
+
+                let alive = grid.get(row as usize, col as usize);
+                let value = match alive {
+                    true => rule.survives(neighbours),
+                    false => rule.is_born(neighbours),
+                };
+                next.set(row as usize, col as usize, value);
+            }
+        }
+
+        next
+    }
+
+    fn random_grid<W: Word>(rows: usize, cols: usize, seed: u64) -> BitGrid<W> {
+        let mut states = random(seed);
+        let mut grid = BitGrid::new(rows, cols);
+
+        for row in 0..rows {
+            for col in 0..cols {
+                grid.set(row, col, states.next().unwrap() >> 61 < 3);
+            }
+        }
+
+        grid
+    }
+
+    fn matches_naive<W: Word>() {
+        let rules = [
+            Rule::LIFE,
+            "B36/S23".parse().unwrap(),
+            Rule::new([0, 1, 8], [0, 4, 8]),
+        ];
+
+        for (rows, cols) in [(1, 1), (1, 5), (5, 5), (7, 70), (70, 7), (33, 130), (3, 64)] {
+            for rule in &rules {
+                for edges in [EdgeMode::Bounded, EdgeMode::Toroidal] {
+                    let mut grid = random_grid::<W>(rows, cols, (rows * cols) as u64);
+
+                    for generation in 0..3 {
+                        let next = grid.step(rule, edges);
+                        assert_eq!(
+                            next,
+                            naive(&grid, rule, edges),
+                            "{rows}x{cols}, {rule}, {edges:?}, generation {generation}"
+                        );
+                        grid = next;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn step_matches_naive() {
+        matches_naive::<u8>();
+        matches_naive::<u64>();
+        matches_naive::<u128>();
+    }
+
+    #[test]
+    fn glider_wraps_around() {
+        let start: BitGrid = ".#......\n..#.....\n###.....\n........\n........"
+            .parse()
+            .unwrap();
+
+        // a glider moves one cell diagonally every four generations
+        let mut grid = start.clone();
+        for _ in 0..4 * 40 {
+            grid = grid.step(&Rule::LIFE, EdgeMode::Toroidal);
+        }
+        assert_eq!(grid, start);
+
+        // while at the bottom edge of a bounded field it settles into a block
+        let mut grid = start.clone();
+        for _ in 0..40 {
+            grid = grid.step(&Rule::LIFE, EdgeMode::Bounded);
+        }
+        assert_eq!(grid.count_set(.., ..), 4);
+        assert!(grid.all_set(3..5, 3..5));
+    }
+
+    #[test]
+    fn rule_queries() {
+        let all = Rule::new(0..=8, 0..=8);
+
+        for neighbours in 0..=u8::MAX {
+            assert_eq!(Rule::LIFE.is_born(neighbours), neighbours == 3);
+            assert_eq!(Rule::LIFE.survives(neighbours), matches!(neighbours, 2 | 3));
+            assert_eq!(all.is_born(neighbours), neighbours <= 8);
+            assert_eq!(all.survives(neighbours), neighbours <= 8);
+        }
+    }
+
+    #[test]
+    fn rule_notation() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::LIFE));
+        assert_eq!("s32/b3".parse(), Ok(Rule::LIFE));
+        assert_eq!(Rule::new([3, 6], [2, 3]).to_string(), "B36/S23");
+        assert_eq!(Rule::new([], []).to_string(), "B/S");
+
+        assert_eq!(
+            "B3/S29".parse::<Rule>(),
+            Err(TightVecError::InvalidCharacter {
+                character: '9',
+                position: 5
+            })
+        );
+        assert_eq!(
+            "3/S23".parse::<Rule>(),
+            Err(TightVecError::InvalidCharacter {
+                character: '3',
+                position: 0
+            })
+        );
+    }
+}
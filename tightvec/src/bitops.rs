@@ -0,0 +1,154 @@
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::{TightVec, Word};
+
+/// The bitwise operations that combine a destination with a source of the same length
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BitOp {
+    And,
+    Or,
+    Xor,
+}
+
+/// `dst = dst <op> src`, word by word. Panics if they differ in length.
+pub(crate) fn combine_words<W: Word>(op: BitOp, dst: &mut [W], src: &[W]) {
+    assert_eq!(dst.len(), src.len());
+
+    // one loop per operation, so each one vectorizes without a branch inside
+    match op {
+        BitOp::And => dst.iter_mut().zip(src).for_each(|(d, &s)| *d &= s),
+        BitOp::Or => dst.iter_mut().zip(src).for_each(|(d, &s)| *d |= s),
+        BitOp::Xor => dst.iter_mut().zip(src).for_each(|(d, &s)| *d ^= s),
+    }
+}
+
+/// Entry-wise logical operations between vectors of the same length, running over whole words.
+/// The binary operators panic if the lengths differ.
+impl<W: Word> TightVec<W> {
+    /// Flips every entry
+    pub fn invert(&mut self) {
+        for word in &mut self.inner {
+            *word = !*word;
+        }
+        self.clear_unused_bits();
+    }
+
+    #[track_caller]
+    fn combine(&mut self, op: BitOp, other: &TightVec<W>) {
+        assert_eq!(
+            self.len, other.len,
+            "bitwise operations need vectors of the same length"
+        );
+
+        combine_words(op, &mut self.inner, &other.inner);
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $kind:expr) => {
+        impl<W: Word> $assign<&TightVec<W>> for TightVec<W> {
+            #[track_caller]
+            fn $assign_method(&mut self, other: &TightVec<W>) {
+                self.combine($kind, other);
+            }
+        }
+
+        impl<W: Word> $op<&TightVec<W>> for TightVec<W> {
+            type Output = TightVec<W>;
+
+            #[track_caller]
+            fn $method(mut self, other: &TightVec<W>) -> TightVec<W> {
+                self.combine($kind, other);
+                self
+            }
+        }
+
+        impl<W: Word> $op<&TightVec<W>> for &TightVec<W> {
+            type Output = TightVec<W>;
+
+            #[track_caller]
+            fn $method(self, other: &TightVec<W>) -> TightVec<W> {
+                self.clone().$method(other)
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, BitOp::And);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, BitOp::Or);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, BitOp::Xor);
+
+impl<W: Word> Not for TightVec<W> {
+    type Output = TightVec<W>;
+
+    fn not(mut self) -> TightVec<W> {
+        self.invert();
+        self
+    }
+}
+
+impl<W: Word> Not for &TightVec<W> {
+    type Output = TightVec<W>;
+
+    fn not(self) -> TightVec<W> {
+        !self.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::pattern;
+    use crate::{TightVec, Word};
+
+    fn entry_wise<W: Word>() {
+        for len in [0, 5, 64, 300, 4100] {
+            let a = pattern::<W>(len, 3);
+            let b = pattern::<W>(len, 7);
+
+            let expected = |f: fn(bool, bool) -> bool| -> TightVec<W> {
+                let mut v = TightVec::default();
+                for (x, y) in a.iter().zip(b.iter()) {
+                    v.push(f(x, y));
+                }
+                v
+            };
+
+            assert_eq!(&a & &b, expected(|x, y| x & y));
+            assert_eq!(&a | &b, expected(|x, y| x | y));
+            assert_eq!(&a ^ &b, expected(|x, y| x ^ y));
+            assert_eq!(!&a, expected(|x, _| !x));
+            assert_eq!(!!a.clone(), a);
+
+            let mut c = a.clone();
+            c ^= &a;
+            assert_eq!(c, TightVec::with_len_and_value(len, false));
+            assert_eq!(c.as_bitslice().count_ones(), 0);
+        }
+    }
+
+    #[test]
+    fn operators() {
+        entry_wise::<u8>();
+        entry_wise::<u64>();
+        entry_wise::<u128>();
+    }
+
+    #[test]
+    fn invert_keeps_padding_clear() {
+        let mut v: TightVec = TightVec::with_len_and_value(70, false);
+
+        v.invert();
+
+        assert_eq!(v.get_raw(), [u64::MAX, (1 << 6) - 1]);
+        assert_eq!(v, TightVec::with_len_and_value(70, true));
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn length_mismatch() {
+        let a: TightVec = TightVec::with_len_and_value(3, true);
+        let b: TightVec = TightVec::with_len_and_value(4, true);
+
+        let _ = &a & &b;
+    }
+}
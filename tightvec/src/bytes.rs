@@ -0,0 +1,227 @@
+//! Compact binary format: the length as a `u64`, followed by the `len.div_ceil(W::BITS)` packed
+//! storage words. Length and words use the same explicit byte order. Bits past `len` in the last
+//! word are always written as zero.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+use crate::{TightVec, TightVecError, Word};
+
+/// Byte order used by the binary format
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// The byte order of the target platform
+    pub const NATIVE: Endianness = if cfg!(target_endian = "big") {
+        Endianness::Big
+    } else {
+        Endianness::Little
+    };
+}
+
+/// size of the length header
+pub(crate) const HEADER_BYTES: usize = 8;
+
+impl<W: Word> TightVec<W> {
+    /// Encodes the vector in the binary format
+    pub fn to_bytes(&self, endianness: Endianness) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_BYTES + self.inner.len() * W::BITS / 8);
+
+        (self.len as u64).write_bytes(endianness, &mut bytes);
+
+        let last_bits = self.len % W::BITS;
+        for (word_index, &word) in self.inner.iter().enumerate() {
+            let word = if word_index + 1 == self.inner.len() && last_bits != 0 {
+                word & W::low_mask(last_bits)
+            } else {
+                word
+            };
+
+            word.write_bytes(endianness, &mut bytes);
+        }
+
+        bytes
+    }
+
+    /// Decodes a vector from the binary format. `bytes` must contain exactly one encoded vector.
+    pub fn from_bytes(bytes: &[u8], endianness: Endianness) -> Result<Self, TightVecError> {
+        let header = bytes
+            .get(..HEADER_BYTES)
+            .ok_or(TightVecError::InvalidByteLength {
+                expected: HEADER_BYTES,
+                actual: bytes.len(),
+            })?;
+
+        let len = Self::decode_len(header, endianness)?;
+        let word_bytes = W::BITS / 8;
+        let expected = len
+            .div_ceil(W::BITS)
+            .checked_mul(word_bytes)
+            .and_then(|words| words.checked_add(HEADER_BYTES))
+            .unwrap_or(usize::MAX);
+
+        if bytes.len() != expected {
+            return Err(TightVecError::InvalidByteLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        let mut v = TightVec {
+            inner: bytes[HEADER_BYTES..]
+                .chunks_exact(word_bytes)
+                .map(|chunk| W::read_bytes(chunk, endianness))
+                .collect(),
+            len,
+        };
+        v.clear_unused_bits();
+
+        Ok(v)
+    }
+
+    /// Writes the vector in the binary format, e.g. to cache a computed field on disk
+    #[cfg(feature = "std")]
+    pub fn write_to(&self, mut writer: impl Write, endianness: Endianness) -> io::Result<()> {
+        writer.write_all(&self.to_bytes(endianness))
+    }
+
+    /// Reads one vector in the binary format, consuming exactly its bytes from `reader`
+    #[cfg(feature = "std")]
+    pub fn read_from(mut reader: impl Read, endianness: Endianness) -> io::Result<Self> {
+        let mut header = [0; HEADER_BYTES];
+        reader.read_exact(&mut header)?;
+
+        let len = Self::decode_len(&header, endianness)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        // the header isn't trusted, so the buffer only grows as far as the data actually goes
+        let byte_len = len.div_ceil(W::BITS) * (W::BITS / 8);
+        let mut words = Vec::new();
+        reader.take(byte_len as u64).read_to_end(&mut words)?;
+
+        if words.len() != byte_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                TightVecError::InvalidByteLength {
+                    expected: HEADER_BYTES + byte_len,
+                    actual: HEADER_BYTES + words.len(),
+                },
+            ));
+        }
+
+        let mut v = TightVec {
+            inner: words
+                .chunks_exact(W::BITS / 8)
+                .map(|chunk| W::read_bytes(chunk, endianness))
+                .collect(),
+            len,
+        };
+        v.clear_unused_bits();
+
+        Ok(v)
+    }
+
+    fn decode_len(header: &[u8], endianness: Endianness) -> Result<usize, TightVecError> {
+        let len = u64::read_bytes(header, endianness);
+
+        usize::try_from(len).map_err(|_| TightVecError::LengthTooLarge { len })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::pattern;
+    use crate::{Endianness, TightVec, TightVecError, Word};
+
+    fn round_trip<W: Word>() {
+        for len in [0, 1, 8, 63, 64, 65, 300] {
+            let v = pattern::<W>(len, 7);
+
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let bytes = v.to_bytes(endianness);
+
+                assert_eq!(bytes.len(), 8 + len.div_ceil(W::BITS) * W::BITS / 8);
+                assert_eq!(TightVec::<W>::from_bytes(&bytes, endianness), Ok(v.clone()));
+
+                #[cfg(feature = "std")]
+                {
+                    let mut file = Vec::new();
+                    v.write_to(&mut file, endianness).unwrap();
+                    v.write_to(&mut file, endianness).unwrap();
+
+                    let mut reader = file.as_slice();
+                    assert_eq!(
+                        TightVec::<W>::read_from(&mut reader, endianness).unwrap(),
+                        v
+                    );
+                    assert_eq!(
+                        TightVec::<W>::read_from(&mut reader, endianness).unwrap(),
+                        v
+                    );
+                    assert!(reader.is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip::<u8>();
+        round_trip::<u32>();
+        round_trip::<u64>();
+        round_trip::<u128>();
+    }
+
+    #[test]
+    fn explicit_layout() {
+        let v: TightVec<u16> = "1000000000000001_1".parse().unwrap();
+
+        assert_eq!(
+            v.to_bytes(Endianness::Little),
+            [17, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x80, 0x01, 0x00]
+        );
+        assert_eq!(
+            v.to_bytes(Endianness::Big),
+            [0, 0, 0, 0, 0, 0, 0, 17, 0x80, 0x01, 0x00, 0x01]
+        );
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let v: TightVec = TightVec::with_len_and_value(70, true);
+        let bytes = v.to_bytes(Endianness::Little);
+
+        assert_eq!(
+            TightVec::<u64>::from_bytes(&bytes[..20], Endianness::Little),
+            Err(TightVecError::InvalidByteLength {
+                expected: 24,
+                actual: 20
+            })
+        );
+        assert_eq!(
+            TightVec::<u64>::from_bytes(&bytes[..3], Endianness::Little),
+            Err(TightVecError::InvalidByteLength {
+                expected: 8,
+                actual: 3
+            })
+        );
+        #[cfg(feature = "std")]
+        assert!(TightVec::<u64>::read_from(&bytes[..20], Endianness::Little).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn oversized_length_header() {
+        let mut bytes = (u64::MAX / 2).to_le_bytes().to_vec();
+        bytes.extend([0xff; 16]);
+
+        let error = TightVec::<u64>::read_from(bytes.as_slice(), Endianness::Little).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert!(TightVec::<u64>::from_bytes(&bytes, Endianness::Little).is_err());
+    }
+}
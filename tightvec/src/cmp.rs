@@ -0,0 +1,135 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use crate::{TightVec, Word};
+
+/// Equality, hashing and ordering only look at the first `len` entries. Whatever is stored in the
+/// last word past `len` is ignored, so two vectors with the same entries always compare equal.
+impl<W: Word> TightVec<W> {
+    /// the storage words with every bit past `len` cleared
+    pub(crate) fn logical_words(&self) -> impl Iterator<Item = W> + '_ {
+        let last_bits = self.len % W::BITS;
+        let last_index = self.inner.len().wrapping_sub(1);
+
+        self.inner
+            .iter()
+            .enumerate()
+            .map(move |(word_index, &word)| {
+                if word_index == last_index && last_bits != 0 {
+                    word & W::low_mask(last_bits)
+                } else {
+                    word
+                }
+            })
+    }
+}
+
+impl<W: Word> PartialEq for TightVec<W> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.len != other.len {
+            return false;
+        }
+
+        let full = self.len / W::BITS;
+        let last_bits = self.len % W::BITS;
+
+        self.inner[..full] == other.inner[..full]
+            && (last_bits == 0
+                || (self.inner[full] ^ other.inner[full]) & W::low_mask(last_bits) == W::ZERO)
+    }
+}
+
+impl<W: Word> Eq for TightVec<W> {}
+
+impl<W: Word> Hash for TightVec<W> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+
+        for word in self.logical_words() {
+            word.hash(state);
+        }
+    }
+}
+
+impl<W: Word> PartialOrd for TightVec<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Lexicographic, like `Vec<bool>`: the first differing entry decides, `false` sorting before `true`.
+/// If one vector is a prefix of the other, the shorter one is smaller.
+impl<W: Word> Ord for TightVec<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let common_len = self.len.min(other.len);
+
+        for (word_index, (&a, &b)) in self.inner.iter().zip(&other.inner).enumerate() {
+            let valid_bits = (common_len - (word_index * W::BITS).min(common_len)).min(W::BITS);
+            let difference = (a ^ b) & W::low_mask(valid_bits);
+
+            if difference != W::ZERO {
+                let first_difference = W::ONE << difference.trailing_zeros() as usize;
+
+                return if a & first_difference != W::ZERO {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                };
+            }
+        }
+
+        self.len.cmp(&other.len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::TightVec;
+    use crate::test_util::{from_bits, pattern_bits};
+
+    #[test]
+    fn garbage_past_len_is_ignored() {
+        let mut a: TightVec = from_bits(&[true, false, true]);
+        let b: TightVec = from_bits(&[true, false, true]);
+
+        a.inner[0] |= 1 << 40;
+
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+
+        let set: HashSet<TightVec> = [a, b].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn different_lengths_differ() {
+        let a: TightVec = TightVec::with_len_and_value(64, false);
+        let b: TightVec = TightVec::with_len_and_value(65, false);
+
+        assert_ne!(a, b);
+        assert!(a < b);
+    }
+
+    #[test]
+    fn ordering_matches_vec_bool() {
+        let vectors: Vec<Vec<bool>> = [0, 1, 5, 63, 64, 65, 130]
+            .into_iter()
+            .flat_map(|len| {
+                [3, 7, 11]
+                    .into_iter()
+                    .map(move |seed| pattern_bits(len, seed))
+            })
+            .collect();
+
+        for a in &vectors {
+            for b in &vectors {
+                let (x, y): (TightVec, TightVec) = (from_bits(a), from_bits(b));
+
+                assert_eq!(x.cmp(&y), a.cmp(b), "{a:?} {b:?}");
+                assert_eq!(x == y, a == b);
+            }
+        }
+    }
+}
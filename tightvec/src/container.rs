@@ -0,0 +1,337 @@
+use alloc::vec::Vec;
+use core::iter;
+use core::ops::RangeBounds;
+
+use crate::error::or_panic;
+use crate::slice::try_resolve_range;
+use crate::{BitSlice, BitSliceMut, TightVec, Word};
+
+/// Read access shared by every bit storage of this crate, so algorithms can be written once
+/// and run on dense or compressed bits alike.
+///
+/// Also implemented for `[bool]` and `Vec<bool>`, which serve as the trivially correct reference
+/// the packed implementations are tested against. All methods panic on out-of-bounds indices and
+/// ranges, like their inherent counterparts.
+pub trait BitContainer {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, index: usize) -> bool;
+
+    /// Number of set entries in the range
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize;
+
+    /// Whether every entry in the range is set. True for an empty range.
+    #[track_caller]
+    fn all_in(&self, range: impl RangeBounds<usize>) -> bool {
+        let (start, end) = or_panic(try_resolve_range(range, self.len()));
+
+        self.count_ones_in(start..end) == end - start
+    }
+
+    /// Whether any entry in the range is set
+    #[track_caller]
+    fn any_in(&self, range: impl RangeBounds<usize>) -> bool {
+        self.count_ones_in(range) > 0
+    }
+
+    /// Indices of the set entries, in increasing order
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_;
+}
+
+/// Write access shared by every mutable bit storage of this crate
+pub trait BitContainerMut: BitContainer {
+    fn set(&mut self, index: usize, value: bool);
+
+    /// Sets every entry in the range to `value`
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool);
+}
+
+impl<W: Word> BitContainer for BitSlice<'_, W> {
+    fn len(&self) -> usize {
+        BitSlice::len(self)
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        self.index(index)
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        self.slice(range).count_ones()
+    }
+
+    #[track_caller]
+    fn all_in(&self, range: impl RangeBounds<usize>) -> bool {
+        self.slice(range).all()
+    }
+
+    #[track_caller]
+    fn any_in(&self, range: impl RangeBounds<usize>) -> bool {
+        self.slice(range).any()
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(*self)
+    }
+}
+
+impl<W: Word> BitContainer for BitSliceMut<'_, W> {
+    fn len(&self) -> usize {
+        BitSliceMut::len(self)
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        self.index(index)
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        self.as_bitslice().count_ones_in(range)
+    }
+
+    #[track_caller]
+    fn all_in(&self, range: impl RangeBounds<usize>) -> bool {
+        self.as_bitslice().all_in(range)
+    }
+
+    #[track_caller]
+    fn any_in(&self, range: impl RangeBounds<usize>) -> bool {
+        self.as_bitslice().any_in(range)
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(self.as_bitslice())
+    }
+}
+
+impl<W: Word> BitContainerMut for BitSliceMut<'_, W> {
+    #[track_caller]
+    fn set(&mut self, index: usize, value: bool) {
+        BitSliceMut::set(self, index, value)
+    }
+
+    #[track_caller]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        self.slice_mut(range).fill(value)
+    }
+}
+
+impl<W: Word> BitContainer for TightVec<W> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        self.index(index)
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        self.slice(range).count_ones()
+    }
+
+    #[track_caller]
+    fn all_in(&self, range: impl RangeBounds<usize>) -> bool {
+        self.slice(range).all()
+    }
+
+    #[track_caller]
+    fn any_in(&self, range: impl RangeBounds<usize>) -> bool {
+        self.slice(range).any()
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(self.as_bitslice())
+    }
+}
+
+impl<W: Word> BitContainerMut for TightVec<W> {
+    #[track_caller]
+    fn set(&mut self, index: usize, value: bool) {
+        TightVec::set(self, index, value)
+    }
+
+    #[track_caller]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        self.slice_mut(range).fill(value)
+    }
+}
+
+impl BitContainer for [bool] {
+    fn len(&self) -> usize {
+        <[bool]>::len(self)
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        self[index]
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        let (start, end) = or_panic(try_resolve_range(range, self.len()));
+
+        self[start..end].iter().filter(|&&bit| bit).count()
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.iter()
+            .enumerate()
+            .filter(|&(_, &bit)| bit)
+            .map(|(index, _)| index)
+    }
+}
+
+impl BitContainerMut for [bool] {
+    #[track_caller]
+    fn set(&mut self, index: usize, value: bool) {
+        self[index] = value;
+    }
+
+    #[track_caller]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        let (start, end) = or_panic(try_resolve_range(range, self.len()));
+
+        self[start..end].fill(value);
+    }
+}
+
+impl BitContainer for Vec<bool> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        self[index]
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        self.as_slice().count_ones_in(range)
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.as_slice().iter_ones()
+    }
+}
+
+impl BitContainerMut for Vec<bool> {
+    #[track_caller]
+    fn set(&mut self, index: usize, value: bool) {
+        self[index] = value;
+    }
+
+    #[track_caller]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        self.as_mut_slice().fill_range(range, value)
+    }
+}
+
+/// indices of the set entries of a view, searching a word at a time
+fn ones<W: Word>(bits: BitSlice<'_, W>) -> impl Iterator<Item = usize> + use<'_, W> {
+    let mut from = 0;
+
+    iter::from_fn(move || {
+        let index = bits.next_index_of(true, from)?;
+        from = index + 1;
+
+        Some(index)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::random;
+    use crate::{BitContainer, BitContainerMut, RunVec, TightVec};
+
+    /// the next number from `rng`, below `bound`
+    fn below(rng: &mut impl Iterator<Item = u64>, bound: usize) -> usize {
+        ((rng.next().unwrap() >> 33) as usize) % bound.max(1)
+    }
+
+    /// applies random edits to `container` and the `Vec<bool>` reference, comparing every query
+    fn check_against_reference(container: &mut (impl BitContainerMut + ?Sized), seed: u64) {
+        let len = container.len();
+        let mut reference: Vec<bool> = (0..len).map(|i| container.get(i)).collect();
+        let mut rng = random(seed);
+
+        for _ in 0..200 {
+            let start = below(&mut rng, len + 1);
+            let end = start + below(&mut rng, len - start + 1);
+            let value = below(&mut rng, 2) == 1;
+
+            if below(&mut rng, 3) == 0 && start < len {
+                container.set(start, value);
+                reference.set(start, value);
+            } else {
+                container.fill_range(start..end, value);
+                reference.fill_range(start..end, value);
+            }
+
+            assert_eq!(container.len(), reference.len());
+            assert!((0..len).all(|i| container.get(i) == reference[i]));
+            assert!(container.iter_ones().eq(reference.iter_ones()));
+            assert_eq!(
+                container.count_ones_in(start..end),
+                reference.count_ones_in(start..end)
+            );
+            assert_eq!(container.all_in(start..end), reference.all_in(start..end));
+            assert_eq!(container.any_in(start..end), reference.any_in(start..end));
+            assert_eq!(container.all_in(..), reference.all_in(..));
+        }
+    }
+
+    #[test]
+    fn tightvec_matches_reference() {
+        for len in [0, 1, 63, 64, 65, 300] {
+            check_against_reference(&mut TightVec::<u8>::with_len_and_value(len, false), 1);
+            check_against_reference(&mut TightVec::<u64>::with_len_and_value(len, true), 2);
+            check_against_reference(&mut TightVec::<u128>::with_len_and_value(len, false), 3);
+        }
+    }
+
+    #[test]
+    fn runvec_matches_reference() {
+        for len in [0, 1, 65, 300] {
+            check_against_reference(&mut RunVec::new(len), 4);
+        }
+    }
+
+    #[test]
+    fn views_match_reference() {
+        let mut v: TightVec = TightVec::with_len_and_value(400, false);
+
+        check_against_reference(&mut v.slice_mut(3..290), 5);
+        check_against_reference(&mut v.slice_mut(64..65), 6);
+
+        check_against_reference(&mut v.slice_mut(..), 7);
+    }
+
+    #[test]
+    fn reference_is_consistent() {
+        let mut bools: Vec<bool> = (0..100).map(|i| i % 3 == 0).collect();
+
+        check_against_reference(&mut bools, 8);
+        check_against_reference(&mut bools[10..90], 9);
+    }
+
+    #[test]
+    fn views_stay_inside_their_range() {
+        let mut v: TightVec = TightVec::with_len_and_value(200, true);
+
+        v.slice_mut(10..150).fill_range(.., false);
+        v.slice_mut(10..150).set(0, true);
+
+        assert_eq!(v.as_bitslice().count_ones_in(..), 61);
+        assert_eq!(v.as_bitslice().iter_ones().take(11).last(), Some(10));
+        assert!(v.as_bitslice().all_in(150..));
+    }
+}
@@ -0,0 +1,75 @@
+use core::fmt::{self, Display, Formatter};
+
+/// Errors returned by the checked (`try_*`) operations of this crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TightVecError {
+    /// An index was at or past the end of the vector
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The (exclusive) end of a range was past the end of the vector
+    RangeOutOfBounds { end: usize, len: usize },
+    /// A range started after its end
+    InvertedRange { start: usize, end: usize },
+    /// A character that isn't part of any bit string representation was found while parsing
+    InvalidCharacter { character: char, position: usize },
+    /// Binary data didn't have the length its header announced
+    InvalidByteLength { expected: usize, actual: usize },
+    /// A length read from binary data doesn't fit into `usize` on this platform
+    LengthTooLarge { len: u64 },
+    /// A row of a grid didn't have the same length as the first one
+    RowLengthMismatch {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// A value had more significant bits than the packed element width
+    ValueTooWide { value: u64, bits: usize },
+}
+
+impl Display for TightVecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TightVecError::IndexOutOfBounds { index, len } => {
+                write!(f, "index {index} out of bounds for length {len}")
+            }
+            TightVecError::RangeOutOfBounds { end, len } => {
+                write!(f, "range end {end} out of bounds for length {len}")
+            }
+            TightVecError::InvertedRange { start, end } => {
+                write!(f, "range start {start} is after range end {end}")
+            }
+            TightVecError::InvalidCharacter {
+                character,
+                position,
+            } => {
+                write!(f, "invalid character {character:?} at position {position}")
+            }
+            TightVecError::InvalidByteLength { expected, actual } => {
+                write!(f, "expected {expected} bytes, got {actual}")
+            }
+            TightVecError::LengthTooLarge { len } => {
+                write!(f, "length {len} doesn't fit into usize")
+            }
+            TightVecError::RowLengthMismatch {
+                row,
+                expected,
+                actual,
+            } => {
+                write!(f, "row {row} has length {actual}, expected {expected}")
+            }
+            TightVecError::ValueTooWide { value, bits } => {
+                write!(f, "value {value} doesn't fit into {bits} bits")
+            }
+        }
+    }
+}
+
+impl core::error::Error for TightVecError {}
+
+/// unwraps the result of a checked operation, panicking with the error message at the caller's location
+#[track_caller]
+pub(crate) fn or_panic<T>(result: Result<T, TightVecError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("{error}"),
+    }
+}
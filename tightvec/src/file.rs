@@ -0,0 +1,954 @@
+//! File-backed bit vectors for fields that don't fit into memory.
+//!
+//! The file uses the binary format of [`TightVec::to_bytes`] with [`Endianness::Little`], so a
+//! vector written with [`TightVec::write_to`] can be opened directly and vice versa. Only the
+//! length header and the storage words are stored; a [`FileBitGrid`] keeps its rows back to back.
+//!
+//! The entries are read and written one word at a time, by index or coordinates and ranges. There
+//! are no [`BitSlice`](crate::BitSlice) views into the file, as paged storage has no words in
+//! memory to borrow; copy the entries into memory with [`FileTightVec::to_tightvec`] for those.
+//!
+//! With the `mmap` feature on unix, the words are memory-mapped and the OS pages them in and out.
+//! Otherwise they are read and written through a small cache of pages; an I/O error while loading
+//! or evicting a page panics, like a failing access to mapped memory would crash the process.
+//!
+//! [`Endianness::Little`]: crate::Endianness::Little
+
+use std::fmt::{self, Debug, Formatter};
+use std::fs::File;
+use std::io;
+use std::ops::{Range, RangeBounds};
+use std::path::Path;
+
+use crate::bytes::HEADER_BYTES;
+use crate::error::or_panic;
+use crate::grid::try_resolve_rect;
+use crate::slice::{check_index, try_resolve_range, word_masks};
+use crate::{BitContainer, BitContainerMut, Endianness, TightVec, TightVecError};
+
+const WORD_BITS: usize = u64::BITS as usize;
+const WORD_BYTES: usize = size_of::<u64>();
+
+/// A fixed-length bit vector whose storage words live in a file instead of on the heap.
+///
+/// Writes reach the file at the latest when the vector is dropped; call [`flush`](Self::flush)
+/// to write them out earlier and see I/O errors.
+pub struct FileTightVec {
+    backend: Backend,
+    len: usize,
+}
+
+/// A two-dimensional field of bits backed by a [`FileTightVec`].
+///
+/// Offers the cell and rectangle operations of [`BitGrid`](crate::BitGrid), but not its row views
+/// or [`BitRows`](crate::BitRows). Unlike a `BitGrid`, rows aren't padded to whole words: the file
+/// holds the cells row-major, back to back, as a vector of `rows * cols` entries.
+pub struct FileBitGrid {
+    bits: FileTightVec,
+    rows: usize,
+    cols: usize,
+}
+
+enum Backend {
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(mapped::Mapped),
+    Paged(paged::Paged),
+}
+
+impl FileTightVec {
+    /// Creates (or truncates) the file at `path` and fills it with `len` unset entries.
+    /// Memory-maps the file if the `mmap` feature is enabled.
+    pub fn create(path: impl AsRef<Path>, len: usize) -> io::Result<Self> {
+        let file = create_file(path.as_ref(), len)?;
+
+        Self::with_backend(file, len)
+    }
+
+    /// Like [`create`](Self::create), but always goes through the page cache
+    pub fn create_paged(path: impl AsRef<Path>, len: usize) -> io::Result<Self> {
+        let file = create_file(path.as_ref(), len)?;
+
+        Ok(Self::paged(file, len))
+    }
+
+    /// Opens a file written by [`TightVec::write_to`] with little-endian byte order, or by a
+    /// previous `FileTightVec`. Memory-maps the file if the `mmap` feature is enabled.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let (file, len) = open_file(path.as_ref())?;
+
+        Self::with_backend(file, len)
+    }
+
+    /// Like [`open`](Self::open), but always goes through the page cache
+    pub fn open_paged(path: impl AsRef<Path>) -> io::Result<Self> {
+        let (file, len) = open_file(path.as_ref())?;
+
+        Ok(Self::paged(file, len))
+    }
+
+    /// Writes `v` to a new file at `path` and opens it
+    pub fn from_tightvec(path: impl AsRef<Path>, v: &TightVec) -> io::Result<Self> {
+        v.write_to(File::create(path.as_ref())?, Endianness::Little)?;
+
+        Self::open(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the words are memory-mapped rather than cached
+    pub fn is_mapped(&self) -> bool {
+        match self.backend {
+            #[cfg(all(feature = "mmap", unix))]
+            Backend::Mapped(_) => true,
+            Backend::Paged(_) => false,
+        }
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn index(&self, index: usize) -> bool {
+        or_panic(self.try_index(index))
+    }
+
+    pub fn try_index(&self, index: usize) -> Result<bool, TightVecError> {
+        check_index(index, self.len)?;
+
+        Ok(self.backend.read(index / WORD_BITS) & 1 << (index % WORD_BITS) != 0)
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: bool) {
+        or_panic(self.try_set(index, value))
+    }
+
+    pub fn try_set(&mut self, index: usize, value: bool) -> Result<(), TightVecError> {
+        check_index(index, self.len)?;
+
+        self.update(index / WORD_BITS, 1 << (index % WORD_BITS), value);
+
+        Ok(())
+    }
+
+    /// Sets every entry in the range to `value`. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        or_panic(self.try_fill_range(range, value))
+    }
+
+    pub fn try_fill_range(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: bool,
+    ) -> Result<(), TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        for (word_index, mask) in word_masks::<u64>(start, end - start) {
+            self.update(word_index, mask, value);
+        }
+
+        Ok(())
+    }
+
+    /// Number of set entries in the range. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        or_panic(self.try_count_ones_in(range))
+    }
+
+    pub fn try_count_ones_in(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<usize, TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        Ok(word_masks::<u64>(start, end - start)
+            .map(|(word_index, mask)| (self.backend.read(word_index) & mask).count_ones() as usize)
+            .sum())
+    }
+
+    /// Copies the entries into memory
+    pub fn to_tightvec(&self) -> TightVec {
+        let mut v = TightVec::with_capacity(self.len);
+
+        for (word_index, mask) in word_masks::<u64>(0, self.len) {
+            v.push_bits(
+                self.backend.read(word_index) & mask,
+                mask.count_ones() as usize,
+            );
+        }
+
+        v
+    }
+
+    /// Writes all changes to the file
+    pub fn flush(&self) -> io::Result<()> {
+        self.backend.flush()
+    }
+
+    fn with_backend(file: File, len: usize) -> io::Result<Self> {
+        #[cfg(all(feature = "mmap", unix))]
+        {
+            let bytes = HEADER_BYTES + TightVec::<u64>::words_for(len) * WORD_BYTES;
+
+            Ok(Self {
+                backend: Backend::Mapped(mapped::Mapped::new(&file, bytes)?),
+                len,
+            })
+        }
+
+        #[cfg(not(all(feature = "mmap", unix)))]
+        Ok(Self::paged(file, len))
+    }
+
+    fn paged(file: File, len: usize) -> Self {
+        Self {
+            backend: Backend::Paged(paged::Paged::new(file, TightVec::<u64>::words_for(len))),
+            len,
+        }
+    }
+
+    /// sets or clears the bits of `mask` in one word
+    fn update(&mut self, word_index: usize, mask: u64, value: bool) {
+        let word = self.backend.read(word_index);
+        let word = if value { word | mask } else { word & !mask };
+
+        self.backend.write(word_index, word);
+    }
+}
+
+impl FileBitGrid {
+    /// Creates (or truncates) the file at `path` and fills it with an empty grid.
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the number of cells overflows `usize`.
+    pub fn create(path: impl AsRef<Path>, rows: usize, cols: usize) -> io::Result<Self> {
+        Ok(Self {
+            bits: FileTightVec::create(path, cell_count(rows, cols)?)?,
+            rows,
+            cols,
+        })
+    }
+
+    /// Like [`create`](Self::create), but always goes through the page cache
+    pub fn create_paged(path: impl AsRef<Path>, rows: usize, cols: usize) -> io::Result<Self> {
+        Ok(Self {
+            bits: FileTightVec::create_paged(path, cell_count(rows, cols)?)?,
+            rows,
+            cols,
+        })
+    }
+
+    /// Opens a grid with rows of `cols` cells. Fails if the stored length isn't a multiple of it.
+    pub fn open(path: impl AsRef<Path>, cols: usize) -> io::Result<Self> {
+        Self::from_bits(FileTightVec::open(path)?, cols)
+    }
+
+    /// Like [`open`](Self::open), but always goes through the page cache
+    pub fn open_paged(path: impl AsRef<Path>, cols: usize) -> io::Result<Self> {
+        Self::from_bits(FileTightVec::open_paged(path)?, cols)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Panics if the cell is out of bounds
+    #[track_caller]
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        or_panic(self.try_get(row, col))
+    }
+
+    pub fn try_get(&self, row: usize, col: usize) -> Result<bool, TightVecError> {
+        self.bits.try_index(self.locate(row, col)?)
+    }
+
+    /// Panics if the cell is out of bounds
+    #[track_caller]
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        or_panic(self.try_set(row, col, value))
+    }
+
+    pub fn try_set(&mut self, row: usize, col: usize, value: bool) -> Result<(), TightVecError> {
+        let index = self.locate(row, col)?;
+
+        self.bits.try_set(index, value)
+    }
+
+    /// Sets every cell of the rectangle to `value`. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn fill_rect(
+        &mut self,
+        rows: impl RangeBounds<usize>,
+        cols: impl RangeBounds<usize>,
+        value: bool,
+    ) {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        for row in rows {
+            let start = row * self.cols;
+            self.bits
+                .fill_range(start + cols.start..start + cols.end, value);
+        }
+    }
+
+    /// Whether every cell of the rectangle is set. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn all_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> bool {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        rows.into_iter()
+            .all(|row| self.count_in_row(row, cols.clone()) == cols.len())
+    }
+
+    /// Whether any cell of the rectangle is set. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn any_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> bool {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        rows.into_iter()
+            .any(|row| self.count_in_row(row, cols.clone()) > 0)
+    }
+
+    /// Number of set cells in the rectangle. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn count_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> usize {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        rows.map(|row| self.count_in_row(row, cols.clone())).sum()
+    }
+
+    /// Writes all changes to the file
+    pub fn flush(&self) -> io::Result<()> {
+        self.bits.flush()
+    }
+
+    /// The underlying row-major vector
+    pub fn as_file_tightvec(&self) -> &FileTightVec {
+        &self.bits
+    }
+
+    fn from_bits(bits: FileTightVec, cols: usize) -> io::Result<Self> {
+        let rows = bits.len().checked_div(cols).unwrap_or(0);
+
+        if rows * cols != bits.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                TightVecError::RowLengthMismatch {
+                    row: rows,
+                    expected: cols,
+                    actual: bits.len() - rows * cols,
+                },
+            ));
+        }
+
+        Ok(Self { bits, rows, cols })
+    }
+
+    fn count_in_row(&self, row: usize, cols: Range<usize>) -> usize {
+        let start = row * self.cols;
+
+        self.bits
+            .count_ones_in(start + cols.start..start + cols.end)
+    }
+
+    fn locate(&self, row: usize, col: usize) -> Result<usize, TightVecError> {
+        check_index(row, self.rows)?;
+        check_index(col, self.cols)?;
+
+        Ok(row * self.cols + col)
+    }
+}
+
+/// Only shows the shape, the entries may not fit into memory
+impl Debug for FileTightVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileTightVec")
+            .field("len", &self.len)
+            .field("mapped", &self.is_mapped())
+            .finish()
+    }
+}
+
+impl Debug for FileBitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileBitGrid")
+            .field("rows", &self.rows)
+            .field("cols", &self.cols)
+            .field("mapped", &self.bits.is_mapped())
+            .finish()
+    }
+}
+
+impl BitContainer for FileTightVec {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        self.index(index)
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        FileTightVec::count_ones_in(self, range)
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        word_masks::<u64>(0, self.len).flat_map(move |(word_index, mask)| {
+            let mut word = self.backend.read(word_index) & mask;
+
+            std::iter::from_fn(move || {
+                let bit = word.trailing_zeros() as usize;
+                word &= word.wrapping_sub(1);
+
+                (bit < WORD_BITS).then_some(word_index * WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+impl BitContainerMut for FileTightVec {
+    #[track_caller]
+    fn set(&mut self, index: usize, value: bool) {
+        FileTightVec::set(self, index, value)
+    }
+
+    #[track_caller]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        FileTightVec::fill_range(self, range, value)
+    }
+}
+
+impl Backend {
+    fn read(&self, word_index: usize) -> u64 {
+        match self {
+            #[cfg(all(feature = "mmap", unix))]
+            Backend::Mapped(mapped) => mapped.read(word_index),
+            Backend::Paged(paged) => paged.read(word_index),
+        }
+    }
+
+    fn write(&mut self, word_index: usize, word: u64) {
+        match self {
+            #[cfg(all(feature = "mmap", unix))]
+            Backend::Mapped(mapped) => mapped.write(word_index, word),
+            Backend::Paged(paged) => paged.write(word_index, word),
+        }
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        match self {
+            #[cfg(all(feature = "mmap", unix))]
+            Backend::Mapped(mapped) => mapped.flush(),
+            Backend::Paged(paged) => paged.flush(),
+        }
+    }
+}
+
+/// `rows * cols`, failing if it overflows
+fn cell_count(rows: usize, cols: usize) -> io::Result<usize> {
+    rows.checked_mul(cols).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("a grid of {rows} × {cols} cells overflows usize"),
+        )
+    })
+}
+
+fn create_file(path: &Path, len: usize) -> io::Result<File> {
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+
+    io::Write::write_all(&mut &file, &(len as u64).to_le_bytes())?;
+    file.set_len((HEADER_BYTES + TightVec::<u64>::words_for(len) * WORD_BYTES) as u64)?;
+
+    Ok(file)
+}
+
+fn open_file(path: &Path) -> io::Result<(File, usize)> {
+    let file = File::options().read(true).write(true).open(path)?;
+
+    let mut header = [0; HEADER_BYTES];
+    io::Read::read_exact(&mut &file, &mut header)?;
+
+    let len = usize::try_from(u64::from_le_bytes(header))
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    let expected = HEADER_BYTES + TightVec::<u64>::words_for(len) * WORD_BYTES;
+    let actual = file.metadata()?.len() as usize;
+
+    if actual != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            TightVecError::InvalidByteLength { expected, actual },
+        ));
+    }
+
+    Ok((file, len))
+}
+
+#[cfg(all(feature = "mmap", unix))]
+mod mapped {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::ptr::{self, NonNull};
+
+    use super::{HEADER_BYTES, WORD_BYTES};
+
+    /// the whole file, mapped shared and writable
+    pub(super) struct Mapped {
+        start: NonNull<u8>,
+        bytes: usize,
+    }
+
+    // SAFETY: the mapping is owned like a heap allocation; reads need `&self`, writes `&mut self`
+    unsafe impl Send for Mapped {}
+    // SAFETY: as above
+    unsafe impl Sync for Mapped {}
+
+    impl Mapped {
+        pub(super) fn new(file: &File, bytes: usize) -> io::Result<Self> {
+            // SAFETY: maps a file we hold open with exactly its length; the result is checked
+            let start = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    bytes,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+
+            if start == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self {
+                start: NonNull::new(start.cast()).expect("mmap returned null"),
+                bytes,
+            })
+        }
+
+        fn word(&self, word_index: usize) -> *mut u64 {
+            let offset = HEADER_BYTES + word_index * WORD_BYTES;
+            assert!(
+                offset + WORD_BYTES <= self.bytes,
+                "word {word_index} outside the mapping"
+            );
+
+            // SAFETY: in bounds as checked above; the mapping is page aligned and the header is
+            // one word long, so the word is aligned
+            unsafe { self.start.as_ptr().add(offset).cast() }
+        }
+
+        pub(super) fn read(&self, word_index: usize) -> u64 {
+            // SAFETY: `word` returns an aligned pointer into the live mapping
+            u64::from_le(unsafe { ptr::read(self.word(word_index)) })
+        }
+
+        pub(super) fn write(&mut self, word_index: usize, word: u64) {
+            // SAFETY: as above, and `&mut self` rules out concurrent access
+            unsafe { ptr::write(self.word(word_index), word.to_le()) }
+        }
+
+        pub(super) fn flush(&self) -> io::Result<()> {
+            // SAFETY: syncs exactly the range we mapped
+            if unsafe { libc::msync(self.start.as_ptr().cast(), self.bytes, libc::MS_SYNC) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Drop for Mapped {
+        fn drop(&mut self) {
+            // SAFETY: unmaps the range mapped in `new`, nothing refers to it afterwards
+            unsafe { libc::munmap(self.start.as_ptr().cast(), self.bytes) };
+        }
+    }
+}
+
+mod paged {
+    use std::collections::{BTreeMap, HashMap};
+    use std::fs::File;
+    use std::io::{self, Read, Seek, SeekFrom, Write};
+    use std::sync::Mutex;
+
+    use super::{HEADER_BYTES, WORD_BYTES};
+
+    /// words per cached page (4 KiB)
+    const PAGE_WORDS: usize = 512;
+    /// pages kept in memory before the least recently used one is written back
+    const MAX_PAGES: usize = 256;
+
+    /// the words, read and written through a cache of pages
+    pub(super) struct Paged {
+        storage: Storage,
+        cache: Mutex<Cache>,
+    }
+
+    struct Storage {
+        file: File,
+        word_count: usize,
+    }
+
+    #[derive(Default)]
+    struct Cache {
+        /// the cached pages, in the order they were loaded into their slots
+        slots: Vec<Page>,
+        /// slot of every cached page, by page index
+        slot_of: HashMap<usize, usize>,
+        /// cached page indices by last use, oldest first
+        lru: BTreeMap<u64, usize>,
+        clock: u64,
+        /// page index and slot of the last access, which sequential accesses hit without a lookup
+        current: Option<(usize, usize)>,
+    }
+
+    struct Page {
+        index: usize,
+        words: Vec<u64>,
+        dirty: bool,
+        last_use: u64,
+    }
+
+    impl Paged {
+        pub(super) fn new(file: File, word_count: usize) -> Self {
+            Self {
+                storage: Storage { file, word_count },
+                cache: Mutex::default(),
+            }
+        }
+
+        pub(super) fn read(&self, word_index: usize) -> u64 {
+            let mut cache = self.cache.lock().unwrap();
+            let slot = cache.slot(&self.storage, word_index / PAGE_WORDS);
+
+            cache.slots[slot].words[word_index % PAGE_WORDS]
+        }
+
+        pub(super) fn write(&mut self, word_index: usize, word: u64) {
+            // exclusive access, no need to lock
+            let cache = self.cache.get_mut().unwrap();
+            let slot = cache.slot(&self.storage, word_index / PAGE_WORDS);
+            let page = &mut cache.slots[slot];
+
+            page.words[word_index % PAGE_WORDS] = word;
+            page.dirty = true;
+        }
+
+        pub(super) fn flush(&self) -> io::Result<()> {
+            let mut cache = self.cache.lock().unwrap();
+
+            for page in &mut cache.slots {
+                self.storage.write_back(page)?;
+            }
+
+            self.storage.file.sync_data()
+        }
+    }
+
+    impl Cache {
+        /// the slot of the cached page, loading it and evicting the least recently used page if
+        /// needed
+        fn slot(&mut self, storage: &Storage, page_index: usize) -> usize {
+            if let Some((current, slot)) = self.current
+                && current == page_index
+            {
+                // already the most recently used page
+                return slot;
+            }
+
+            assert!(
+                page_index * PAGE_WORDS < storage.word_count,
+                "page {page_index} outside the file"
+            );
+
+            self.clock += 1;
+            let now = self.clock;
+
+            let slot = match self.slot_of.get(&page_index) {
+                Some(&slot) => {
+                    self.lru.remove(&self.slots[slot].last_use);
+                    slot
+                }
+                None => {
+                    let page = storage.load(page_index).unwrap_or_else(|error| {
+                        panic!("reading page {page_index} failed: {error}")
+                    });
+
+                    let slot = if self.slots.len() < MAX_PAGES {
+                        self.slots.push(page);
+                        self.slots.len() - 1
+                    } else {
+                        let slot = self.evict(storage);
+                        self.slots[slot] = page;
+                        slot
+                    };
+
+                    self.slot_of.insert(page_index, slot);
+                    slot
+                }
+            };
+
+            self.slots[slot].last_use = now;
+            self.lru.insert(now, page_index);
+            self.current = Some((page_index, slot));
+
+            slot
+        }
+
+        /// writes back the least recently used page and returns its now unused slot
+        fn evict(&mut self, storage: &Storage) -> usize {
+            let (_, oldest) = self.lru.pop_first().unwrap();
+            let slot = self.slot_of.remove(&oldest).unwrap();
+
+            storage
+                .write_back(&mut self.slots[slot])
+                .unwrap_or_else(|error| panic!("writing page {oldest} failed: {error}"));
+
+            slot
+        }
+    }
+
+    impl Storage {
+        fn load(&self, page_index: usize) -> io::Result<Page> {
+            let first_word = page_index * PAGE_WORDS;
+            let word_count = PAGE_WORDS.min(self.word_count - first_word);
+            let mut bytes = vec![0; word_count * WORD_BYTES];
+
+            let mut file = &self.file;
+            file.seek(SeekFrom::Start(offset(first_word)))?;
+            file.read_exact(&mut bytes)?;
+
+            Ok(Page {
+                index: page_index,
+                words: bytes
+                    .chunks_exact(WORD_BYTES)
+                    .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+                    .collect(),
+                dirty: false,
+                last_use: 0,
+            })
+        }
+
+        fn write_back(&self, page: &mut Page) -> io::Result<()> {
+            if !page.dirty {
+                return Ok(());
+            }
+
+            let bytes: Vec<u8> = page
+                .words
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect();
+
+            let mut file = &self.file;
+            file.seek(SeekFrom::Start(offset(page.index * PAGE_WORDS)))?;
+            file.write_all(&bytes)?;
+
+            page.dirty = false;
+
+            Ok(())
+        }
+    }
+
+    impl Drop for Paged {
+        fn drop(&mut self) {
+            // errors can't be reported from here, `flush` exists to see them
+            let _ = self.flush();
+        }
+    }
+
+    fn offset(word_index: usize) -> u64 {
+        (HEADER_BYTES + word_index * WORD_BYTES) as u64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::{fs, io};
+
+    use crate::test_util::pattern;
+    use crate::{
+        BitContainer, BitGrid, Endianness, FileBitGrid, FileTightVec, TightVec, TightVecError,
+    };
+
+    /// a fresh path in the temp directory, removed again on drop
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("tightvec-{}-{name}.bits", std::process::id())))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn exercise(create: fn(&TempFile, usize) -> io::Result<FileTightVec>, name: &str) {
+        let file = TempFile::new(name);
+        let len = 300_000;
+
+        let mut v = create(&file, len).unwrap();
+        let mut reference: TightVec = TightVec::with_len_and_value(len, false);
+
+        for (start, end, value) in [
+            (5, 200_000, true),
+            (70, 130, false),
+            (299_990, 300_000, true),
+        ] {
+            v.fill_range(start..end, value);
+            reference.slice_mut(start..end).fill(value);
+        }
+        v.set(100, true);
+        reference.set(100, true);
+
+        assert_eq!(v.count_ones_in(..), reference.as_bitslice().count_ones());
+        assert_eq!(v.count_ones_in(60..140), 21);
+        assert!(v.index(100) && !v.index(101));
+        assert!(v.iter_ones().eq(reference.iter_ones()));
+        assert_eq!(
+            v.try_index(len),
+            Err(TightVecError::IndexOutOfBounds { index: len, len })
+        );
+
+        v.flush().unwrap();
+        drop(v);
+
+        let bytes = fs::read(&file.0).unwrap();
+        assert_eq!(
+            TightVec::<u64>::from_bytes(&bytes, Endianness::Little),
+            Ok(reference.clone())
+        );
+        assert_eq!(
+            FileTightVec::open(&file.0).unwrap().to_tightvec(),
+            reference
+        );
+    }
+
+    #[test]
+    fn paged() {
+        exercise(
+            |file, len| FileTightVec::create_paged(&file.0, len),
+            "paged",
+        );
+    }
+
+    #[test]
+    fn paged_eviction() {
+        // 4 KiB pages, more of them than the cache holds
+        let (pages, page_bits) = (300, 512 * 64);
+        let file = TempFile::new("eviction");
+        let mut v = FileTightVec::create_paged(&file.0, pages * page_bits).unwrap();
+
+        // the backwards pass reloads the pages evicted by the forwards one
+        for page in 0..pages {
+            v.set(page * page_bits + page % 64, true);
+        }
+        for page in (0..pages).rev() {
+            assert!(v.index(page * page_bits + page % 64));
+            v.set(page * page_bits + 100, true);
+        }
+        assert_eq!(v.count_ones_in(..), 2 * pages);
+        drop(v);
+
+        let reopened = FileTightVec::open(&file.0).unwrap().to_tightvec();
+        assert!(reopened.iter_ones().eq(
+            (0..pages).flat_map(|page| { [page * page_bits + page % 64, page * page_bits + 100] })
+        ));
+    }
+
+    #[test]
+    fn default_backend() {
+        exercise(|file, len| FileTightVec::create(&file.0, len), "default");
+
+        let file = TempFile::new("mapped-flag");
+        let v = FileTightVec::create(&file.0, 10).unwrap();
+        assert_eq!(v.is_mapped(), cfg!(all(feature = "mmap", unix)));
+    }
+
+    #[test]
+    fn opens_tightvec_files() {
+        let file = TempFile::new("compat");
+        let v = pattern(1000, 7);
+
+        let opened = FileTightVec::from_tightvec(&file.0, &v).unwrap();
+        assert_eq!(opened.to_tightvec(), v);
+        drop(opened);
+
+        let paged = FileTightVec::open_paged(&file.0).unwrap();
+        assert_eq!(paged.len(), 1000);
+        assert_eq!(paged.count_ones_in(..), v.as_bitslice().count_ones());
+
+        fs::write(&file.0, &v.to_bytes(Endianness::Little)[..20]).unwrap();
+        assert_eq!(
+            FileTightVec::open(&file.0).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let file = TempFile::new("grid");
+        let mut grid = FileBitGrid::create_paged(&file.0, 50, 70).unwrap();
+        let mut reference: BitGrid = BitGrid::new(50, 70);
+
+        for (rows, cols) in [(3..10, 5..60), (20..21, 0..70), (30..50, 69..70)] {
+            grid.fill_rect(rows.clone(), cols.clone(), true);
+            reference.fill_rect(rows, cols, true);
+        }
+        grid.set(5, 10, false);
+        reference.set(5, 10, false);
+
+        assert_eq!(grid.count_set(.., ..), reference.count_set(.., ..));
+        assert!(grid.all_set(6..10, 5..60));
+        assert!(!grid.all_set(3..10, 5..60));
+        assert!(grid.any_set(.., 69..));
+        assert!(!grid.get(5, 10));
+        drop(grid);
+
+        let grid = FileBitGrid::open(&file.0, 70).unwrap();
+        assert_eq!(grid.rows(), 50);
+        assert!(
+            (0..50).all(|row| (0..70).all(|col| grid.get(row, col) == reference.get(row, col)))
+        );
+        drop(grid);
+
+        assert!(FileBitGrid::open(&file.0, 64).is_err());
+    }
+
+    #[test]
+    fn oversized_grid() {
+        let file = TempFile::new("oversized");
+
+        assert_eq!(
+            FileBitGrid::create(&file.0, usize::MAX / 2, 3)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert!(!file.0.exists());
+    }
+}
@@ -0,0 +1,346 @@
+//! Text representations of bit vectors.
+//!
+//! Entries are always written in index order, index 0 first:
+//!
+//! | format   | example (`1101_0001_1`)           |
+//! |----------|-----------------------------------|
+//! | `{}`     | `110100011`                       |
+//! | `{:#}`   | `##.#...##`                       |
+//! | `{:b}`   | `110100011`                       |
+//! | `{:#b}`  | `0b11010001_1`                    |
+//! | `{:x}`   | `d18`                             |
+//! | `{:#x}`  | `0xd18`                           |
+//!
+//! Every hex digit holds four consecutive entries, the first one in its most significant bit, so
+//! the hex string reads like the binary string in groups of four. The last digit is padded with
+//! `false` entries. `{:#b}` and `{:#x}` group the digits with `_` every 8 binary or 4 hex digits.
+//!
+//! All of these can be parsed back with [`str::parse`], except `{:x}` and `{:X}`: hex is only
+//! recognized by its `0x` prefix, and a hex string without it that happens to consist of `0`s and
+//! `1`s would parse as binary. Use `{:#x}` to write hex that round-trips. Parsing a hex string
+//! always yields a multiple of four entries.
+
+use core::fmt::{self, Alignment, Binary, Debug, Display, Formatter, LowerHex, UpperHex, Write};
+use core::str::FromStr;
+
+use crate::{BitSlice, BitSliceMut, TightVec, TightVecError, Word};
+
+impl<W: Word> Display for BitSlice<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (zero, one) = if f.alternate() {
+            ('.', '#')
+        } else {
+            ('0', '1')
+        };
+
+        pad(f, self.len(), |f| {
+            for (start, end, value) in self.runs() {
+                let c = if value { one } else { zero };
+                for _ in start..=end {
+                    f.write_char(c)?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<W: Word> Debug for BitSlice<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitSlice")
+            .field("len", &self.len())
+            .field("bits", &format_args!("{self}"))
+            .finish()
+    }
+}
+
+impl<W: Word> Debug for BitSliceMut<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitSliceMut")
+            .field("len", &self.len())
+            .field("bits", &format_args!("{}", self.as_bitslice()))
+            .finish()
+    }
+}
+
+impl<W: Word> Binary for BitSlice<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let digits = self.iter().map(|bit| if bit { '1' } else { '0' });
+
+        write_digits(f, digits, "0b", 8)
+    }
+}
+
+impl<W: Word> LowerHex for BitSlice<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_digits(f, self.hex_digits(false), "0x", 4)
+    }
+}
+
+impl<W: Word> UpperHex for BitSlice<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_digits(f, self.hex_digits(true), "0x", 4)
+    }
+}
+
+impl<W: Word> BitSlice<'_, W> {
+    fn hex_digits(&self, uppercase: bool) -> impl ExactSizeIterator<Item = char> + '_ {
+        (0..self.len().div_ceil(4)).map(move |digit_index| {
+            // words hold a whole number of digits, so a digit never spans two chunks
+            let first = digit_index * 4;
+            let (chunk, _) = self.chunk(first / W::BITS);
+            let nibble = ((chunk >> (first % W::BITS)).to_u64() as u8).reverse_bits() >> 4;
+            let digit = char::from_digit(nibble as u32, 16).unwrap();
+
+            if uppercase {
+                digit.to_ascii_uppercase()
+            } else {
+                digit
+            }
+        })
+    }
+}
+
+impl<W: Word> Display for TightVec<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.as_bitslice(), f)
+    }
+}
+
+impl<W: Word> Debug for TightVec<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TightVec")
+            .field("len", &self.len)
+            .field("bits", &format_args!("{}", self.as_bitslice()))
+            .finish()
+    }
+}
+
+impl<W: Word> Binary for TightVec<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Binary::fmt(&self.as_bitslice(), f)
+    }
+}
+
+impl<W: Word> LowerHex for TightVec<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        LowerHex::fmt(&self.as_bitslice(), f)
+    }
+}
+
+impl<W: Word> UpperHex for TightVec<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        UpperHex::fmt(&self.as_bitslice(), f)
+    }
+}
+
+/// Parses any of the representations written by the formatting traits; hex needs its `0x` prefix,
+/// as written by `{:#x}`. Leading and trailing whitespace is ignored, `_` may be used as a
+/// separator anywhere.
+impl<W: Word> FromStr for TightVec<W> {
+    type Err = TightVecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let start = s.len() - s.trim_start().len();
+
+        let mut v = TightVec::with_capacity(trimmed.len());
+
+        if let Some(digits) = trimmed.strip_prefix("0x") {
+            for (position, character) in digits.char_indices() {
+                match character.to_digit(16) {
+                    Some(nibble) => {
+                        for bit in (0..4).rev() {
+                            v.push(nibble & (1 << bit) != 0);
+                        }
+                    }
+                    None if character == '_' => {}
+                    None => return Err(invalid(character, start + 2 + position)),
+                }
+            }
+        } else {
+            let (digits, prefix_len) = match trimmed.strip_prefix("0b") {
+                Some(digits) => (digits, 2),
+                None => (trimmed, 0),
+            };
+
+            for (position, character) in digits.char_indices() {
+                match character {
+                    '0' => v.push(false),
+                    '1' => v.push(true),
+                    '.' if prefix_len == 0 => v.push(false),
+                    '#' if prefix_len == 0 => v.push(true),
+                    '_' => {}
+                    _ => return Err(invalid(character, start + prefix_len + position)),
+                }
+            }
+        }
+
+        Ok(v)
+    }
+}
+
+fn invalid(character: char, position: usize) -> TightVecError {
+    TightVecError::InvalidCharacter {
+        character,
+        position,
+    }
+}
+
+/// writes `digits`, adding `prefix` and a `_` every `group` digits in alternate mode
+fn write_digits(
+    f: &mut Formatter<'_>,
+    digits: impl ExactSizeIterator<Item = char>,
+    prefix: &str,
+    group: usize,
+) -> fmt::Result {
+    let count = digits.len();
+    let alternate = f.alternate();
+    let width = match alternate {
+        true => prefix.len() + count + count.div_ceil(group).saturating_sub(1),
+        false => count,
+    };
+
+    pad(f, width, |f| {
+        if alternate {
+            f.write_str(prefix)?;
+        }
+
+        for (index, digit) in digits.enumerate() {
+            if alternate && index > 0 && index % group == 0 {
+                f.write_char('_')?;
+            }
+            f.write_char(digit)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// surrounds the `len` characters written by `write` with fill characters up to the requested
+/// width, aligned to the left unless requested otherwise, like [`Formatter::pad`] does for strings
+fn pad(
+    f: &mut Formatter<'_>,
+    len: usize,
+    write: impl FnOnce(&mut Formatter<'_>) -> fmt::Result,
+) -> fmt::Result {
+    let padding = f.width().unwrap_or(0).saturating_sub(len);
+    let (before, after) = match f.align() {
+        Some(Alignment::Right) => (padding, 0),
+        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(Alignment::Left) | None => (0, padding),
+    };
+    let fill = f.fill();
+
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    write(f)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::pattern;
+    use crate::{TightVec, TightVecError};
+
+    fn example() -> TightVec {
+        "110100011".parse().unwrap()
+    }
+
+    #[test]
+    fn formats() {
+        let v = example();
+
+        assert_eq!(format!("{v}"), "110100011");
+        assert_eq!(format!("{v:#}"), "##.#...##");
+        assert_eq!(format!("{v:b}"), "110100011");
+        assert_eq!(format!("{v:#b}"), "0b11010001_1");
+        assert_eq!(format!("{v:x}"), "d18");
+        assert_eq!(format!("{v:X}"), "D18");
+        assert_eq!(format!("{v:#x}"), "0xd18");
+        assert_eq!(format!("{v:>12}"), "   110100011");
+        assert_eq!(format!("{v:*^13}"), "**110100011**");
+        assert_eq!(format!("{v:<#8x}"), "0xd18   ");
+        assert_eq!(format!("{v:#14b}"), "0b11010001_1  ");
+        assert_eq!(format!("{v:?}"), "TightVec { len: 9, bits: 110100011 }");
+        assert_eq!(format!("{}", v.slice(2..6)), "0100");
+    }
+
+    #[test]
+    fn grouped_hex() {
+        let v: TightVec = TightVec::with_len_and_value(36, true);
+
+        assert_eq!(format!("{v:#x}"), "0xffff_ffff_f");
+    }
+
+    #[test]
+    fn round_trips() {
+        for len in [0usize, 1, 4, 9, 64, 65, 200] {
+            let v: TightVec = pattern(len, 7);
+
+            for text in [
+                format!("{v}"),
+                format!("{v:#}"),
+                format!("{v:b}"),
+                format!("{v:#b}"),
+            ] {
+                assert_eq!(text.parse::<TightVec>().unwrap(), v, "{text}");
+            }
+
+            let mut padded = v.clone();
+            padded.resize(len.div_ceil(4) * 4, false);
+
+            for text in [format!("{v:#x}"), format!("{v:#X}")] {
+                assert_eq!(text.parse::<TightVec>().unwrap(), padded, "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn hex_needs_its_prefix() {
+        let v: TightVec = "0001_0000".parse().unwrap();
+
+        // without the prefix, the digits of `{:x}` are read as binary
+        assert_eq!(format!("{v:x}"), "10");
+        assert_eq!(format!("{v:x}").parse::<TightVec>().unwrap().len(), 2);
+        assert_eq!(format!("{v:#x}").parse::<TightVec>().unwrap(), v);
+    }
+
+    #[test]
+    fn parses_fixture_rows() {
+        let v: TightVec = "  ..##.#\n".parse().unwrap();
+
+        assert_eq!(v, "001101".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "01x1".parse::<TightVec>(),
+            Err(TightVecError::InvalidCharacter {
+                character: 'x',
+                position: 2
+            })
+        );
+        assert_eq!(
+            " 0b1#".parse::<TightVec>(),
+            Err(TightVecError::InvalidCharacter {
+                character: '#',
+                position: 4
+            })
+        );
+        assert_eq!(
+            "0xfg".parse::<TightVec>(),
+            Err(TightVecError::InvalidCharacter {
+                character: 'g',
+                position: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,537 @@
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::ops::{Range, RangeBounds};
+use core::str::FromStr;
+
+use crate::error::or_panic;
+use crate::slice::{check_index, try_resolve_range};
+use crate::{BitSlice, BitSliceMut, StorageItem, TightVec, TightVecError, Word};
+
+/// A two-dimensional field of bits, stored row-major in one contiguous allocation.
+///
+/// Every row starts at a word boundary, so row views never need to shift. The padding bits after
+/// the last column of each row are always zero.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitGrid<W: Word = StorageItem> {
+    words: Vec<W>,
+    rows: usize,
+    cols: usize,
+    words_per_row: usize,
+}
+
+/// Anything that can be read as equally long rows of bits: a [`BitGrid`] or a slice of [`TightVec`]s.
+/// Lets grid algorithms accept both the contiguous grid and the one-vector-per-row layout.
+pub trait BitRows {
+    type Word: Word;
+
+    fn rows(&self) -> usize;
+
+    fn cols(&self) -> usize;
+
+    fn row(&self, row: usize) -> BitSlice<'_, Self::Word>;
+}
+
+/// Iterator over the `(row, col)` coordinates of all set cells of a [`BitGrid`], in row-major order
+#[derive(Clone, Debug)]
+pub struct Ones<'a, W: Word = StorageItem> {
+    grid: &'a BitGrid<W>,
+    word_index: usize,
+    current: W,
+}
+
+impl<W: Word> BitGrid<W> {
+    /// Creates a grid with every cell unset. Panics if the number of storage words overflows `usize`.
+    #[track_caller]
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self::with_value(rows, cols, false)
+    }
+
+    /// Creates a grid with every cell set to `value`. Panics if the number of storage words
+    /// overflows `usize`.
+    #[track_caller]
+    pub fn with_value(rows: usize, cols: usize, value: bool) -> Self {
+        let words_per_row = cols.div_ceil(W::BITS);
+        let word_count = rows
+            .checked_mul(words_per_row)
+            .expect("grid size overflows usize");
+
+        let mut grid = Self {
+            words: vec![W::fill_value(value); word_count],
+            rows,
+            cols,
+            words_per_row,
+        };
+        grid.clear_padding();
+
+        grid
+    }
+
+    /// Copies equally long rows into a grid. Panics if the rows differ in length.
+    #[track_caller]
+    pub fn from_rows(rows: &[TightVec<W>]) -> Self {
+        or_panic(Self::try_from_rows(rows))
+    }
+
+    pub fn try_from_rows(rows: &[TightVec<W>]) -> Result<Self, TightVecError> {
+        let cols = common_len(rows)?;
+        let mut grid = Self::new(rows.len(), cols);
+
+        for (row_index, row) in rows.iter().enumerate() {
+            grid.row_mut(row_index)
+                .copy_from_bitslice(&row.as_bitslice());
+        }
+
+        Ok(grid)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Storage words per row, including the padding of the last one
+    pub fn words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    /// The row-major storage words
+    pub fn get_raw(&self) -> &[W] {
+        &self.words
+    }
+
+    /// Panics if the cell is out of bounds
+    #[track_caller]
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        or_panic(self.try_get(row, col))
+    }
+
+    pub fn try_get(&self, row: usize, col: usize) -> Result<bool, TightVecError> {
+        let (word_index, mask) = self.locate(row, col)?;
+
+        Ok(self.words[word_index] & mask != W::ZERO)
+    }
+
+    /// Panics if the cell is out of bounds
+    #[track_caller]
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        or_panic(self.try_set(row, col, value))
+    }
+
+    pub fn try_set(&mut self, row: usize, col: usize, value: bool) -> Result<(), TightVecError> {
+        let (word_index, mask) = self.locate(row, col)?;
+
+        if value {
+            self.words[word_index] |= mask;
+        } else {
+            self.words[word_index] &= !mask;
+        }
+
+        Ok(())
+    }
+
+    /// Read-only view of one row. Panics if the row is out of bounds.
+    #[track_caller]
+    pub fn row(&self, row: usize) -> BitSlice<'_, W> {
+        or_panic(self.try_row(row))
+    }
+
+    pub fn try_row(&self, row: usize) -> Result<BitSlice<'_, W>, TightVecError> {
+        check_index(row, self.rows)?;
+
+        Ok(BitSlice::new(self.row_words(row), 0, self.cols))
+    }
+
+    /// Mutable view of one row. Panics if the row is out of bounds.
+    #[track_caller]
+    pub fn row_mut(&mut self, row: usize) -> BitSliceMut<'_, W> {
+        or_panic(self.try_row_mut(row))
+    }
+
+    pub fn try_row_mut(&mut self, row: usize) -> Result<BitSliceMut<'_, W>, TightVecError> {
+        check_index(row, self.rows)?;
+
+        let cols = self.cols;
+        let start = row * self.words_per_row;
+
+        Ok(BitSliceMut::new(
+            &mut self.words[start..start + self.words_per_row],
+            0,
+            cols,
+        ))
+    }
+
+    /// Sets every cell of the rectangle to `value`. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn fill_rect(
+        &mut self,
+        rows: impl RangeBounds<usize>,
+        cols: impl RangeBounds<usize>,
+        value: bool,
+    ) {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        for row in rows {
+            self.row_mut(row).slice_mut(cols.clone()).fill(value);
+        }
+    }
+
+    /// Whether every cell of the rectangle is set. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn all_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> bool {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        rows.into_iter()
+            .all(|row| self.row(row).slice(cols.clone()).all())
+    }
+
+    /// Whether any cell of the rectangle is set. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn any_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> bool {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        rows.into_iter()
+            .any(|row| self.row(row).slice(cols.clone()).any())
+    }
+
+    /// Number of set cells in the rectangle. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn count_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> usize {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+
+        rows.map(|row| self.row(row).slice(cols.clone()).count_ones())
+            .sum()
+    }
+
+    /// Iterates over the coordinates of all set cells, row by row
+    pub fn iter_ones(&self) -> Ones<'_, W> {
+        Ones {
+            grid: self,
+            word_index: 0,
+            current: self.words.first().copied().unwrap_or(W::ZERO),
+        }
+    }
+
+    /// storage words of one row
+    fn row_words(&self, row: usize) -> &[W] {
+        &self.words[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    /// word index and bit mask of a cell
+    fn locate(&self, row: usize, col: usize) -> Result<(usize, W), TightVecError> {
+        check_index(row, self.rows)?;
+        check_index(col, self.cols)?;
+
+        Ok((
+            row * self.words_per_row + col / W::BITS,
+            W::ONE << (col % W::BITS),
+        ))
+    }
+
+    /// the row-major storage words, callers have to keep the padding clear
+    pub(crate) fn words_mut(&mut self) -> &mut [W] {
+        &mut self.words
+    }
+
+    /// zeroes the bits after the last column of every row
+    pub(crate) fn clear_padding(&mut self) {
+        let used = self.cols % W::BITS;
+
+        if used == 0 {
+            return;
+        }
+
+        for row in self.words.chunks_exact_mut(self.words_per_row) {
+            row[self.words_per_row - 1] &= W::low_mask(used);
+        }
+    }
+}
+
+impl<W: Word> Iterator for Ones<'_, W> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        while self.current == W::ZERO {
+            self.word_index += 1;
+            self.current = *self.grid.words.get(self.word_index)?;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        self.current ^= W::ONE << bit;
+
+        let words_per_row = self.grid.words_per_row;
+
+        Some((
+            self.word_index / words_per_row,
+            (self.word_index % words_per_row) * W::BITS + bit,
+        ))
+    }
+}
+
+impl<W: Word> BitRows for BitGrid<W> {
+    type Word = W;
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn row(&self, row: usize) -> BitSlice<'_, W> {
+        BitGrid::row(self, row)
+    }
+}
+
+/// All rows must have the same length. [`cols`](BitRows::cols) is the length of the first row,
+/// and [`row`](BitRows::row) panics with [`TightVecError::RowLengthMismatch`] if the requested row
+/// differs from it. Both take constant time; use [`BitGrid::try_from_rows`] to check all rows at
+/// once instead.
+impl<W: Word> BitRows for [TightVec<W>] {
+    type Word = W;
+
+    fn rows(&self) -> usize {
+        self.len()
+    }
+
+    fn cols(&self) -> usize {
+        self.first().map_or(0, TightVec::len)
+    }
+
+    #[track_caller]
+    fn row(&self, row: usize) -> BitSlice<'_, W> {
+        let (expected, actual) = (self.cols(), self[row].len());
+
+        if actual != expected {
+            or_panic(Err(TightVecError::RowLengthMismatch {
+                row,
+                expected,
+                actual,
+            }))
+        }
+
+        self[row].as_bitslice()
+    }
+}
+
+impl<W: Word> BitRows for Vec<TightVec<W>> {
+    type Word = W;
+
+    fn rows(&self) -> usize {
+        self.as_slice().rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.as_slice().cols()
+    }
+
+    #[track_caller]
+    fn row(&self, row: usize) -> BitSlice<'_, W> {
+        self.as_slice().row(row)
+    }
+}
+
+/// One line per row, formatted like a [`TightVec`]: `0`/`1`, or `.`/`#` with `{:#}`
+impl<W: Word> Display for BitGrid<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                f.write_str("\n")?;
+            }
+
+            Display::fmt(&self.row(row), f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Word> Debug for BitGrid<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitGrid")
+            .field("rows", &self.rows)
+            .field("cols", &self.cols)
+            .field(
+                "cells",
+                &(0..self.rows)
+                    .map(|row| format!("{}", self.row(row)))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Parses one row per line, in any of the formats [`TightVec`] parses. Empty lines are skipped.
+impl<W: Word> FromStr for BitGrid<W> {
+    type Err = TightVecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<TightVec<W>>, _>>()?;
+
+        Self::try_from_rows(&rows)
+    }
+}
+
+/// the length of the first row, failing if any other row differs from it
+pub(crate) fn common_len<W: Word>(rows: &[TightVec<W>]) -> Result<usize, TightVecError> {
+    let cols = rows.first().map_or(0, TightVec::len);
+
+    match rows
+        .iter()
+        .map(TightVec::len)
+        .enumerate()
+        .find(|&(_, len)| len != cols)
+    {
+        Some((row, actual)) => Err(TightVecError::RowLengthMismatch {
+            row,
+            expected: cols,
+            actual,
+        }),
+        None => Ok(cols),
+    }
+}
+
+/// converts both ranges of a rectangle, failing if it doesn't fit into `row_count` × `col_count`
+pub(crate) fn try_resolve_rect(
+    rows: impl RangeBounds<usize>,
+    cols: impl RangeBounds<usize>,
+    row_count: usize,
+    col_count: usize,
+) -> Result<(Range<usize>, Range<usize>), TightVecError> {
+    let (row_start, row_end) = try_resolve_range(rows, row_count)?;
+    let (col_start, col_end) = try_resolve_range(cols, col_count)?;
+
+    Ok((row_start..row_end, col_start..col_end))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{BitGrid, TightVec, TightVecError};
+
+    fn example() -> BitGrid {
+        "..#..\n.###.\n#####\n.###.\n..#..".parse().unwrap()
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut grid: BitGrid<u8> = BitGrid::new(3, 20);
+
+        grid.set(1, 17, true);
+        grid.set(2, 0, true);
+        grid.set(2, 0, false);
+
+        assert!(grid.get(1, 17));
+        assert!(!grid.get(2, 0));
+        assert_eq!(grid.iter_ones().collect::<Vec<_>>(), [(1, 17)]);
+        assert_eq!(
+            grid.try_get(3, 0),
+            Err(TightVecError::IndexOutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            grid.try_set(0, 20, true),
+            Err(TightVecError::IndexOutOfBounds { index: 20, len: 20 })
+        );
+    }
+
+    #[test]
+    fn with_value_keeps_padding_clear() {
+        let grid: BitGrid = BitGrid::with_value(3, 70, true);
+
+        assert_eq!(grid.words_per_row(), 2);
+        assert_eq!(grid.get_raw()[1], (1 << 6) - 1);
+        assert_eq!(grid.count_set(.., ..), 210);
+        assert_eq!(grid.iter_ones().count(), 210);
+    }
+
+    #[test]
+    fn rectangles() {
+        let mut grid: BitGrid = BitGrid::new(10, 200);
+
+        grid.fill_rect(2..=5, 60..=140, true);
+
+        assert!(grid.all_set(2..=5, 60..=140));
+        assert!(!grid.all_set(1..=5, 60..=140));
+        assert!(!grid.all_set(2..=5, 60..=141));
+        assert!(grid.any_set(.., 140..));
+        assert!(!grid.any_set(6.., ..));
+        assert_eq!(grid.count_set(.., ..), 4 * 81);
+        assert_eq!(grid.count_set(3..4, 100..), 41);
+
+        grid.fill_rect(3..=4, 70..80, false);
+        assert_eq!(grid.count_set(.., ..), 4 * 81 - 20);
+    }
+
+    #[test]
+    fn rows() {
+        let mut grid = example();
+
+        assert_eq!(format!("{}", grid.row(1)), "01110");
+
+        grid.row_mut(0).fill(true);
+        assert_eq!(format!("{:#}", grid.row(0)), "#####");
+        assert!(grid.try_row(5).is_err());
+    }
+
+    #[test]
+    fn iter_ones() {
+        let grid = example();
+
+        let ones: Vec<(usize, usize)> = grid.iter_ones().collect();
+
+        assert_eq!(ones.len(), 13);
+        assert_eq!(ones[0], (0, 2));
+        assert_eq!(ones[12], (4, 2));
+        assert!(ones.iter().all(|&(row, col)| grid.get(row, col)));
+    }
+
+    #[test]
+    fn display_and_parse() {
+        let grid = example();
+
+        assert_eq!(format!("{grid:#}"), "..#..\n.###.\n#####\n.###.\n..#..");
+        assert_eq!(format!("{grid}").parse::<BitGrid>().unwrap(), grid);
+        assert_eq!(
+            "##\n#".parse::<BitGrid>(),
+            Err(TightVecError::RowLengthMismatch {
+                row: 1,
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn from_rows() {
+        let rows: Vec<TightVec> = vec!["0110".parse().unwrap(), "1001".parse().unwrap()];
+
+        let grid = BitGrid::from_rows(&rows);
+
+        assert_eq!(format!("{grid}"), "0110\n1001");
+        assert_eq!(BitGrid::try_from_rows(&rows), Ok(grid));
+
+        let ragged: Vec<TightVec> = vec!["0110".parse().unwrap(), "100".parse().unwrap()];
+        assert_eq!(
+            BitGrid::try_from_rows(&ragged),
+            Err(TightVecError::RowLengthMismatch {
+                row: 1,
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "grid size overflows usize")]
+    fn oversized_grid() {
+        BitGrid::<u8>::new(usize::MAX, 16);
+    }
+}
@@ -1,18 +1,83 @@
-pub const ITEM_SIZE: usize = 64;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod atomic;
+mod automaton;
+mod bitops;
+mod bytes;
+mod cmp;
+mod container;
+mod error;
+#[cfg(feature = "std")]
+mod file;
+mod fmt;
+mod grid;
+mod packed;
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(feature = "std")]
+mod pnm;
+mod regions;
+mod rle;
+mod sat;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod set;
+mod shift;
+mod simd;
+mod slice;
+#[cfg(test)]
+mod test_util;
+mod transpose;
+mod undo;
+mod word;
+
+pub use atomic::{AtomicTightVec, AtomicWord};
+pub use automaton::{EdgeMode, Rule};
+pub use bytes::Endianness;
+pub use container::{BitContainer, BitContainerMut};
+pub use error::TightVecError;
+#[cfg(feature = "std")]
+pub use file::{FileBitGrid, FileTightVec};
+pub use grid::{BitGrid, BitRows, Ones};
+pub use packed::PackedVec;
+#[cfg(feature = "std")]
+pub use parallel::ParallelConfig;
+#[cfg(feature = "std")]
+pub use pnm::{PbmFormat, read_pbm, write_pbm, write_pgm};
+pub use regions::{Components, Connectivity};
+pub use rle::RunVec;
+pub use sat::SummedAreaTable;
+pub use set::BitSet;
+pub use slice::{BitSlice, BitSliceMut, Iter, Runs};
+pub use transpose::BitColumns;
+pub use undo::{Checkpoint, UndoTightVec};
+pub use word::Word;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+use error::or_panic;
+
+/// Number of bits in the default storage word
+pub const ITEM_SIZE: usize = StorageItem::BITS as usize;
+/// The default storage word
 pub type StorageItem = u64;
 
-#[derive(Default, Clone, Debug)]
-pub struct TightVec {
-    inner: Vec<StorageItem>,
+#[derive(Default, Clone)]
+pub struct TightVec<W: Word = StorageItem> {
+    inner: Vec<W>,
     len: usize,
 }
 
-impl TightVec {
+impl<W: Word> TightVec<W> {
     pub fn with_len_and_value(len: usize, value: bool) -> Self {
         let remainder = Self::rem(len);
 
         let mut v = Self {
-            inner: vec![Self::fill_value(value); len / ITEM_SIZE],
+            inner: vec![W::fill_value(value); len / W::BITS],
             len: len - remainder,
         };
 
@@ -23,7 +88,7 @@ impl TightVec {
         v
     }
 
-    pub fn get_raw(&self) -> &[StorageItem] {
+    pub fn get_raw(&self) -> &[W] {
         &self.inner
     }
 
@@ -35,28 +100,73 @@ impl TightVec {
         self.len == 0
     }
 
+    /// Panics if `index` is out of bounds
+    #[track_caller]
     pub fn index(&self, index: usize) -> bool {
-        let mask = 1 << Self::rem(index);
+        or_panic(self.try_index(index))
+    }
 
-        self.inner_index(index) & mask == mask
+    pub fn try_index(&self, index: usize) -> Result<bool, TightVecError> {
+        self.check_index(index)?;
+
+        // SAFETY: bounds checked above
+        Ok(unsafe { self.index_unchecked(index) })
     }
 
-    pub fn try_index(&self, index: usize) -> Option<bool> {
-        if index > self.len - 1 {
-            return None;
-        }
+    /// Reads an entry without bounds checking
+    ///
+    /// # Safety
+    ///
+    /// `index` must be below `len()`.
+    pub unsafe fn index_unchecked(&self, index: usize) -> bool {
+        debug_assert!(
+            index < self.len,
+            "index {index} out of bounds for length {}",
+            self.len
+        );
+
+        let mask = Self::mask_for_index(index);
 
-        Some(self.index(index))
+        // SAFETY: the caller guarantees `index < len`, so the word exists
+        unsafe { *self.inner.get_unchecked(index / W::BITS) & mask == mask }
     }
 
+    /// Panics if `index` is out of bounds
+    #[track_caller]
     pub fn set(&mut self, index: usize, value: bool) {
+        or_panic(self.try_set(index, value))
+    }
+
+    pub fn try_set(&mut self, index: usize, value: bool) -> Result<(), TightVecError> {
+        self.check_index(index)?;
+
+        // SAFETY: bounds checked above
+        unsafe { self.set_unchecked(index, value) };
+
+        Ok(())
+    }
+
+    /// Writes an entry without bounds checking
+    ///
+    /// # Safety
+    ///
+    /// `index` must be below `len()`.
+    pub unsafe fn set_unchecked(&mut self, index: usize, value: bool) {
+        debug_assert!(
+            index < self.len,
+            "index {index} out of bounds for length {}",
+            self.len
+        );
+
         let mask = Self::mask_for_index(index);
 
+        // SAFETY: the caller guarantees `index < len`, so the word exists
+        let word = unsafe { self.inner.get_unchecked_mut(index / W::BITS) };
+
         if !value {
-            let inverted_mask = u64::MAX - mask;
-            *self.inner_index_mut(index) &= inverted_mask;
+            *word &= !mask;
         } else {
-            *self.inner_index_mut(index) |= mask;
+            *word |= mask;
         }
     }
 
@@ -64,62 +174,377 @@ impl TightVec {
         let new_index = self.len;
         self.len += 1;
 
-        if self.len == 0 || (self.len / ITEM_SIZE) >= self.inner.len() {
-            self.inner.push(0);
+        if Self::words_for(self.len) > self.inner.len() {
+            self.inner.push(W::ZERO);
         }
 
         self.set(new_index, value);
     }
 
-    /// Fills multiple consecutive entries with the same value
-    pub fn fill_multiple(&mut self, start_index: usize, end_index_inclusive: usize, value: bool) {
-        let mut current_index = start_index;
+    /// Removes the last entry and returns it, or `None` if the vector is empty
+    pub fn pop(&mut self) -> Option<bool> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let value = self.index(self.len - 1);
+        self.truncate(self.len - 1);
+
+        Some(value)
+    }
+
+    /// Inserts an entry at `index`, moving all following entries up by one. Panics if `index > len`.
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, value: bool) {
+        or_panic(self.try_insert(index, value))
+    }
+
+    pub fn try_insert(&mut self, index: usize, value: bool) -> Result<(), TightVecError> {
+        if index > self.len {
+            return Err(TightVecError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        self.push(false);
+
+        let first_word = index / W::BITS;
+        let mut carry = W::ZERO;
+
+        for word in &mut self.inner[first_word + 1..] {
+            let next_carry = *word >> (W::BITS - 1);
+            *word = (*word << 1) | carry;
+            carry = next_carry;
+        }
+
+        let word = &mut self.inner[first_word];
+        let low_mask = W::low_mask(Self::rem(index));
+        let carry_out = *word >> (W::BITS - 1);
+
+        *word = (*word & low_mask) | ((*word & !low_mask) << 1);
+
+        if let Some(next) = self.inner.get_mut(first_word + 1) {
+            *next |= carry_out;
+        }
+
+        self.set(index, value);
+
+        Ok(())
+    }
+
+    /// Removes the entry at `index` and returns it, moving all following entries down by one.
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn remove(&mut self, index: usize) -> bool {
+        or_panic(self.try_remove(index))
+    }
+
+    pub fn try_remove(&mut self, index: usize) -> Result<bool, TightVecError> {
+        let value = self.try_index(index)?;
+
+        let first_word = index / W::BITS;
+        let low_mask = W::low_mask(Self::rem(index));
+
+        let word = self.inner[first_word];
+        self.inner[first_word] = (word & low_mask) | ((word >> 1) & !low_mask);
+
+        for word_index in first_word..self.inner.len() - 1 {
+            let carry = self.inner[word_index + 1] & W::ONE;
+            self.inner[word_index] |= carry << (W::BITS - 1);
+            self.inner[word_index + 1] >>= 1;
+        }
+
+        self.len -= 1;
+        self.inner.truncate(Self::words_for(self.len));
+
+        Ok(value)
+    }
+
+    /// Shortens the vector to `len` entries. Has no effect if the vector is already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        self.len = len;
+        self.inner.truncate(Self::words_for(len));
+        self.clear_unused_bits();
+    }
+
+    /// Resizes the vector to `new_len`, filling new entries with `value`
+    pub fn resize(&mut self, new_len: usize, value: bool) {
+        if new_len <= self.len {
+            self.truncate(new_len);
+            return;
+        }
+
+        let old_len = self.len;
+
+        self.inner.resize(Self::words_for(new_len), W::ZERO);
+        self.len = new_len;
+
+        if value {
+            self.fill_multiple(old_len, new_len - 1, true);
+        }
+    }
+
+    /// Removes all entries, keeping the allocated capacity
+    pub fn clear(&mut self) {
+        self.inner.clear();
+        self.len = 0;
+    }
+
+    /// Appends all entries of `other` to the end of this vector
+    pub fn extend_from_tightvec(&mut self, other: &TightVec<W>) {
+        self.clear_unused_bits();
+
+        let offset = Self::rem(self.len);
+
+        if offset == 0 {
+            self.inner.extend_from_slice(&other.inner);
+        } else {
+            for &word in &other.inner {
+                *self.inner.last_mut().unwrap() |= word << offset;
+                self.inner.push(word >> (W::BITS - offset));
+            }
+        }
+
+        self.len += other.len;
+        self.inner.truncate(Self::words_for(self.len));
+        self.clear_unused_bits();
+    }
+
+    /// Moves all entries of `other` to the end of this vector, leaving `other` empty
+    pub fn append(&mut self, other: &mut TightVec<W>) {
+        self.extend_from_tightvec(other);
+        other.clear();
+    }
+
+    /// Splits the vector in two at `at`. Returns the entries `[at, len)`, keeping `[0, at)` in `self`.
+    /// Panics if `at > len`.
+    #[track_caller]
+    pub fn split_off(&mut self, at: usize) -> TightVec<W> {
+        or_panic(self.try_split_off(at))
+    }
 
-        while !current_index.is_multiple_of(ITEM_SIZE) && current_index < end_index_inclusive {
-            self.set(current_index, value);
-            current_index += 1;
+    pub fn try_split_off(&mut self, at: usize) -> Result<TightVec<W>, TightVecError> {
+        if at > self.len {
+            return Err(TightVecError::IndexOutOfBounds {
+                index: at,
+                len: self.len,
+            });
         }
 
-        while end_index_inclusive - current_index > ITEM_SIZE {
-            *self.inner_index_mut(current_index) = Self::fill_value(value);
-            current_index += ITEM_SIZE;
+        let first_word = at / W::BITS;
+        let offset = Self::rem(at);
+
+        let mut tail = TightVec {
+            inner: self.inner[first_word..].to_vec(),
+            len: self.len - at,
+        };
+
+        if offset != 0 {
+            for word_index in 0..tail.inner.len() {
+                let next = tail.inner.get(word_index + 1).copied().unwrap_or(W::ZERO);
+                tail.inner[word_index] =
+                    (tail.inner[word_index] >> offset) | (next << (W::BITS - offset));
+            }
         }
 
-        while current_index <= end_index_inclusive {
-            self.set(current_index, value);
-            current_index += 1;
+        tail.inner.truncate(Self::words_for(tail.len));
+        tail.clear_unused_bits();
+
+        self.truncate(at);
+
+        Ok(tail)
+    }
+
+    /// Swaps the entries at the two indices. Panics if either is out of bounds.
+    #[track_caller]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        or_panic(self.try_swap(a, b))
+    }
+
+    pub fn try_swap(&mut self, a: usize, b: usize) -> Result<(), TightVecError> {
+        let value_a = self.try_index(a)?;
+        let value_b = self.try_index(b)?;
+
+        self.set(a, value_b);
+        self.set(b, value_a);
+
+        Ok(())
+    }
+
+    /// Creates an empty vector with room for at least `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Vec::with_capacity(Self::words_for(capacity)),
+            len: 0,
         }
     }
 
-    /// reference to the inner store containing the value for the given external index
-    fn inner_index(&self, index: usize) -> &u64 {
-        &self.inner[index / ITEM_SIZE]
+    /// Reserves room for at least `additional` more entries
+    pub fn reserve(&mut self, additional: usize) {
+        let needed_words = Self::words_for(self.len + additional);
+        self.inner
+            .reserve(needed_words.saturating_sub(self.inner.len()));
     }
 
-    /// mutable reference to the inner store containing the value for the given external index
-    fn inner_index_mut(&mut self, index: usize) -> &mut u64 {
-        &mut self.inner[index / ITEM_SIZE]
+    /// Shrinks the allocation as close to the current length as possible
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    /// Number of entries the vector can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity() * W::BITS
+    }
+
+    /// Read-only view of all entries
+    pub fn as_bitslice(&self) -> BitSlice<'_, W> {
+        BitSlice::new(&self.inner, 0, self.len)
+    }
+
+    /// Mutable view of all entries
+    pub fn as_bitslice_mut(&mut self) -> BitSliceMut<'_, W> {
+        BitSliceMut::new(&mut self.inner, 0, self.len)
+    }
+
+    /// Read-only view of the entries in `range`, without copying. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> BitSlice<'_, W> {
+        or_panic(self.try_slice(range))
+    }
+
+    pub fn try_slice(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<BitSlice<'_, W>, TightVecError> {
+        self.as_bitslice().try_slice(range)
+    }
+
+    /// Mutable view of the entries in `range`, without copying. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn slice_mut(&mut self, range: impl RangeBounds<usize>) -> BitSliceMut<'_, W> {
+        or_panic(self.try_slice_mut(range))
+    }
+
+    pub fn try_slice_mut(
+        &mut self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<BitSliceMut<'_, W>, TightVecError> {
+        let (start, end) = slice::try_resolve_range(range, self.len)?;
+
+        Ok(BitSliceMut::new(&mut self.inner, start, end - start))
+    }
+
+    pub fn iter(&self) -> Iter<'_, W> {
+        self.as_bitslice().iter()
+    }
+
+    /// Iterates over the maximal runs of equal entries as `(start, end_inclusive, value)`
+    pub fn runs(&self) -> Runs<'_, W> {
+        self.as_bitslice().runs()
+    }
+
+    /// Fills multiple consecutive entries with the same value.
+    /// Panics if the range is inverted or out of bounds.
+    #[track_caller]
+    pub fn fill_multiple(&mut self, start_index: usize, end_index_inclusive: usize, value: bool) {
+        or_panic(self.try_fill_multiple(start_index, end_index_inclusive, value))
+    }
+
+    pub fn try_fill_multiple(
+        &mut self,
+        start_index: usize,
+        end_index_inclusive: usize,
+        value: bool,
+    ) -> Result<(), TightVecError> {
+        if start_index > end_index_inclusive {
+            return Err(TightVecError::InvertedRange {
+                start: start_index,
+                end: end_index_inclusive,
+            });
+        }
+
+        self.check_index(end_index_inclusive)?;
+
+        // the range is validated, so the view can skip its own checks
+        BitSliceMut::new(
+            &mut self.inner,
+            start_index,
+            end_index_inclusive + 1 - start_index,
+        )
+        .fill(value);
+
+        Ok(())
+    }
+
+    /// appends the lowest `bits` bits of `value`, lowest bit first
+    pub(crate) fn push_bits(&mut self, value: W, bits: usize) {
+        if bits == 0 {
+            return;
+        }
+
+        let value = value & W::low_mask(bits);
+        let offset = Self::rem(self.len);
+
+        if offset == 0 {
+            self.inner.push(value);
+        } else {
+            *self.inner.last_mut().unwrap() |= value << offset;
+
+            if offset + bits > W::BITS {
+                self.inner.push(value >> (W::BITS - offset));
+            }
+        }
+
+        self.len += bits;
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), TightVecError> {
+        if index >= self.len {
+            return Err(TightVecError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+
+        Ok(())
     }
 
     /// remainder when converting external -> internal index
     fn rem(index: usize) -> usize {
-        index % ITEM_SIZE
+        index % W::BITS
     }
 
     /// the bit mask for one storage unit used to manipulate an item with the given external index
-    fn mask_for_index(index: usize) -> StorageItem {
-        1 << Self::rem(index)
+    fn mask_for_index(index: usize) -> W {
+        W::ONE << Self::rem(index)
     }
 
-    /// the internal storage value to fill one entire storage unit with the input value provided
-    fn fill_value(value: bool) -> StorageItem {
-        if value { StorageItem::MAX } else { 0 }
+    /// number of storage units needed to hold `len` entries
+    pub(crate) fn words_for(len: usize) -> usize {
+        len.div_ceil(W::BITS)
+    }
+
+    /// zeroes the bits past `len` in the last storage unit, so they can't leak back in when growing
+    fn clear_unused_bits(&mut self) {
+        let used = Self::rem(self.len);
+
+        if used != 0
+            && let Some(last) = self.inner.last_mut()
+        {
+            *last &= W::low_mask(used);
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::TightVec;
+    use crate::test_util::{from_bits, pattern_bits};
+    use crate::{TightVec, TightVecError, Word};
 
     #[test]
     fn push_1() {
@@ -205,23 +630,29 @@ mod test {
             assert!(x.index(i));
         }
 
-        assert_eq!(x.try_index(11), None);
+        assert_eq!(
+            x.try_index(11),
+            Err(TightVecError::IndexOutOfBounds { index: 11, len: 10 })
+        );
     }
 
     #[test]
     fn try_index() {
-        let mut v = TightVec::default();
+        let mut v: TightVec = TightVec::default();
         v.push(false);
         v.push(true);
 
-        assert_eq!(v.try_index(0), Some(false));
-        assert_eq!(v.try_index(1), Some(true));
-        assert_eq!(v.try_index(2), None);
+        assert_eq!(v.try_index(0), Ok(false));
+        assert_eq!(v.try_index(1), Ok(true));
+        assert_eq!(
+            v.try_index(2),
+            Err(TightVecError::IndexOutOfBounds { index: 2, len: 2 })
+        );
     }
 
     #[test]
     fn fill_stretch() {
-        let mut v = TightVec::with_len_and_value(20, false);
+        let mut v: TightVec = TightVec::with_len_and_value(20, false);
 
         v.fill_multiple(5, 18, true);
 
@@ -234,6 +665,271 @@ mod test {
         }
 
         assert!(!v.index(19));
-        assert_eq!(v.try_index(20), None);
+        assert_eq!(
+            v.try_index(20),
+            Err(TightVecError::IndexOutOfBounds { index: 20, len: 20 })
+        );
+    }
+
+    fn to_bits(v: &TightVec) -> Vec<bool> {
+        (0..v.len()).map(|i| v.index(i)).collect()
+    }
+
+    #[test]
+    fn push_allocates_words_lazily() {
+        let mut v: TightVec = TightVec::default();
+
+        for _ in 0..64 {
+            v.push(true);
+        }
+        assert_eq!(v.get_raw().len(), 1);
+
+        v.push(true);
+        assert_eq!(v.get_raw().len(), 2);
+    }
+
+    #[test]
+    fn pop() {
+        let mut v: TightVec = from_bits(&[true, false, true]);
+
+        assert_eq!(v.pop(), Some(true));
+        assert_eq!(v.pop(), Some(false));
+        assert_eq!(v.pop(), Some(true));
+        assert_eq!(v.pop(), None);
+        assert!(v.is_empty());
+        assert!(v.get_raw().is_empty());
+    }
+
+    #[test]
+    fn insert_across_words() {
+        let mut expected = pattern_bits(200, 7);
+        let mut v = from_bits(&expected);
+
+        for (index, value) in [
+            (0, true),
+            (63, false),
+            (64, true),
+            (130, true),
+            (203, false),
+        ] {
+            v.insert(index, value);
+            expected.insert(index, value);
+            assert_eq!(to_bits(&v), expected);
+        }
+
+        assert_eq!(v.get_raw().len(), 4);
+    }
+
+    #[test]
+    fn remove_across_words() {
+        let mut expected = pattern_bits(200, 7);
+        let mut v = from_bits(&expected);
+
+        for index in [0, 63, 64, 127, 150, 194] {
+            assert_eq!(v.remove(index), expected.remove(index));
+            assert_eq!(to_bits(&v), expected);
+        }
+
+        assert_eq!(v.get_raw().len(), 4);
+        assert_eq!(*v.get_raw().last().unwrap() >> (v.len() % 64), 0);
+    }
+
+    #[test]
+    fn truncate_clears_tail() {
+        let mut v: TightVec = TightVec::with_len_and_value(100, true);
+
+        v.truncate(70);
+        assert_eq!(v.len(), 70);
+        assert_eq!(v.get_raw().len(), 2);
+
+        v.resize(100, false);
+        assert!((0..70).all(|i| v.index(i)));
+        assert!((70..100).all(|i| !v.index(i)));
+    }
+
+    #[test]
+    fn resize_grow_with_value() {
+        let mut v = from_bits(&[false, true]);
+
+        v.resize(150, true);
+
+        assert_eq!(v.len(), 150);
+        assert!(!v.index(0));
+        assert!((1..150).all(|i| v.index(i)));
+
+        v.resize(1, true);
+        assert_eq!(to_bits(&v), [false]);
+    }
+
+    #[test]
+    fn clear() {
+        let mut v: TightVec = TightVec::with_len_and_value(100, true);
+
+        v.clear();
+
+        assert!(v.is_empty());
+        assert!(v.capacity() >= 100);
+    }
+
+    #[test]
+    fn extend_and_append() {
+        for first_len in [0, 1, 63, 64, 65, 130] {
+            let first = pattern_bits(first_len, 7);
+            let second: Vec<bool> = pattern_bits(97, 7).into_iter().map(|b| !b).collect();
+
+            let mut v = from_bits(&first);
+            let mut other = from_bits(&second);
+            v.append(&mut other);
+
+            let expected: Vec<bool> = first.iter().chain(&second).copied().collect();
+            assert_eq!(to_bits(&v), expected);
+            assert_eq!(v.get_raw().len(), expected.len().div_ceil(64));
+            assert!(other.is_empty());
+        }
+    }
+
+    #[test]
+    fn split_off() {
+        for at in [0, 1, 63, 64, 100, 199, 200] {
+            let bits = pattern_bits(200, 7);
+            let mut v = from_bits(&bits);
+
+            let tail = v.split_off(at);
+
+            assert_eq!(to_bits(&v), bits[..at]);
+            assert_eq!(to_bits(&tail), bits[at..]);
+            assert_eq!(tail.get_raw().len(), (200 - at).div_ceil(64));
+        }
+    }
+
+    #[test]
+    fn swap() {
+        let mut v = from_bits(&[true, false, false]);
+
+        v.swap(0, 2);
+
+        assert_eq!(to_bits(&v), [false, false, true]);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut v: TightVec = TightVec::with_capacity(65);
+        assert!(v.capacity() >= 128);
+
+        v.reserve(1000);
+        assert!(v.capacity() >= 1000);
+
+        v.push(true);
+        v.shrink_to_fit();
+        assert!(v.capacity() >= v.len());
+    }
+
+    fn exercise_word<W: Word>() {
+        let mut expected = pattern_bits(300, 7);
+        let mut v: TightVec<W> = TightVec::default();
+        for &bit in &expected {
+            v.push(bit);
+        }
+        assert_eq!(v.get_raw().len(), 300usize.div_ceil(W::BITS));
+
+        v.fill_multiple(20, 260, true);
+        expected[20..=260].fill(true);
+
+        for (index, value) in [(0, false), (7, true), (150, false), (303, true)] {
+            v.insert(index, value);
+            expected.insert(index, value);
+        }
+        for index in [1, 8, 9, 250] {
+            assert_eq!(v.remove(index), expected.remove(index));
+        }
+
+        let tail = v.split_off(123);
+        assert!(v.iter().eq(expected[..123].iter().copied()));
+        assert!(tail.iter().eq(expected[123..].iter().copied()));
+
+        v.slice_mut(3..50).copy_from_bitslice(&tail.slice(11..58));
+        expected.copy_within(123 + 11..123 + 58, 3);
+        assert!(v.iter().eq(expected[..123].iter().copied()));
+        assert_eq!(
+            v.slice(3..100).count_ones(),
+            expected[3..100].iter().filter(|&&b| b).count()
+        );
+    }
+
+    #[test]
+    fn all_word_widths() {
+        exercise_word::<u8>();
+        exercise_word::<u16>();
+        exercise_word::<u32>();
+        exercise_word::<u64>();
+        exercise_word::<u128>();
+    }
+
+    #[test]
+    fn try_index_on_empty() {
+        let v: TightVec = TightVec::default();
+
+        assert_eq!(
+            v.try_index(0),
+            Err(TightVecError::IndexOutOfBounds { index: 0, len: 0 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "index 10 out of bounds for length 10")]
+    fn set_past_len_inside_last_word() {
+        let mut v: TightVec = TightVec::with_len_and_value(10, false);
+
+        v.set(10, true);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 12 out of bounds for length 10")]
+    fn index_past_len_inside_last_word() {
+        let v: TightVec = TightVec::with_len_and_value(10, false);
+
+        v.index(12);
+    }
+
+    #[test]
+    fn checked_mutations() {
+        let mut v: TightVec = TightVec::with_len_and_value(10, false);
+
+        assert_eq!(
+            v.try_set(10, true),
+            Err(TightVecError::IndexOutOfBounds { index: 10, len: 10 })
+        );
+        assert_eq!(
+            v.try_fill_multiple(5, 4, true),
+            Err(TightVecError::InvertedRange { start: 5, end: 4 })
+        );
+        assert_eq!(
+            v.try_fill_multiple(5, 10, true),
+            Err(TightVecError::IndexOutOfBounds { index: 10, len: 10 })
+        );
+        assert_eq!(
+            v.try_insert(11, true),
+            Err(TightVecError::IndexOutOfBounds { index: 11, len: 10 })
+        );
+        assert!(v.try_remove(10).is_err());
+        assert!(v.try_swap(0, 10).is_err());
+        assert!(v.try_split_off(11).is_err());
+
+        assert_eq!(v.len(), 10);
+        assert_eq!(v.slice(..).count_ones(), 0);
+
+        assert_eq!(v.try_insert(10, true), Ok(()));
+        assert_eq!(v.try_remove(10), Ok(true));
+    }
+
+    #[test]
+    fn unchecked_access() {
+        let mut v: TightVec = TightVec::with_len_and_value(10, false);
+
+        unsafe {
+            v.set_unchecked(3, true);
+            assert!(v.index_unchecked(3));
+            assert!(!v.index_unchecked(4));
+        }
     }
 }
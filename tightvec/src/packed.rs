@@ -0,0 +1,275 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
+
+use crate::error::or_panic;
+use crate::slice::check_index;
+use crate::{StorageItem, TightVec, TightVecError, Word};
+
+/// A vector of unsigned integers that are `BITS` bits wide each, packed back to back.
+///
+/// Element `i` occupies the bits `i * BITS .. (i + 1) * BITS` of the underlying [`TightVec`],
+/// lowest bit first, so an element may straddle two (or, with narrow words, more) storage words.
+/// `BITS` must be between 1 and 64; other widths fail to compile.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PackedVec<const BITS: usize, W: Word = StorageItem> {
+    bits: TightVec<W>,
+}
+
+impl<const BITS: usize, W: Word> PackedVec<BITS, W> {
+    /// The largest value an element can hold
+    pub const MAX: u64 = {
+        assert!(
+            BITS >= 1 && BITS <= 64,
+            "element width must be between 1 and 64 bits"
+        );
+
+        u64::MAX >> (64 - BITS)
+    };
+
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a vector of `len` copies of `value`. Panics if `value` doesn't fit into `BITS` bits.
+    #[track_caller]
+    pub fn with_len_and_value(len: usize, value: u64) -> Self {
+        or_panic(check_value::<BITS>(value));
+
+        let mut v = Self::with_capacity(len);
+        for _ in 0..len {
+            v.push(value);
+        }
+
+        v
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        // rejects unsupported widths at compile time
+        let _ = Self::MAX;
+
+        Self {
+            bits: TightVec::with_capacity(capacity * BITS),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len() / BITS
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn index(&self, index: usize) -> u64 {
+        or_panic(self.try_index(index))
+    }
+
+    pub fn try_index(&self, index: usize) -> Result<u64, TightVecError> {
+        check_index(index, self.len())?;
+
+        let bits = self.bits.slice(index * BITS..(index + 1) * BITS);
+
+        Ok((0..bits.chunk_count()).fold(0, |value, chunk_index| {
+            value | bits.chunk(chunk_index).0.to_u64() << (chunk_index * W::BITS)
+        }))
+    }
+
+    /// Panics if `index` is out of bounds or `value` doesn't fit into `BITS` bits
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: u64) {
+        or_panic(self.try_set(index, value))
+    }
+
+    pub fn try_set(&mut self, index: usize, value: u64) -> Result<(), TightVecError> {
+        check_index(index, self.len())?;
+        check_value::<BITS>(value)?;
+
+        let mut bits = self.bits.slice_mut(index * BITS..(index + 1) * BITS);
+
+        for (start, chunk_bits) in chunks::<BITS, W>() {
+            bits.store_bits(start, W::from_u64(value >> start), chunk_bits);
+        }
+
+        Ok(())
+    }
+
+    /// Panics if `value` doesn't fit into `BITS` bits
+    #[track_caller]
+    pub fn push(&mut self, value: u64) {
+        or_panic(self.try_push(value))
+    }
+
+    pub fn try_push(&mut self, value: u64) -> Result<(), TightVecError> {
+        check_value::<BITS>(value)?;
+
+        for (start, chunk_bits) in chunks::<BITS, W>() {
+            self.bits.push_bits(W::from_u64(value >> start), chunk_bits);
+        }
+
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<u64> {
+        let value = self.try_index(self.len().checked_sub(1)?).ok()?;
+        self.bits.truncate(self.bits.len() - BITS);
+
+        Some(value)
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.bits.truncate(len * BITS);
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = u64> + ExactSizeIterator + '_ {
+        (0..self.len()).map(|index| self.index(index))
+    }
+
+    /// The packed bits, `BITS` per element
+    pub fn as_tightvec(&self) -> &TightVec<W> {
+        &self.bits
+    }
+
+    /// The storage words
+    pub fn get_raw(&self) -> &[W] {
+        self.bits.get_raw()
+    }
+}
+
+/// `(first bit, bit count)` of every word-sized piece of an element
+fn chunks<const BITS: usize, W: Word>() -> impl Iterator<Item = (usize, usize)> {
+    (0..BITS)
+        .step_by(W::BITS)
+        .map(|start| (start, (BITS - start).min(W::BITS)))
+}
+
+fn check_value<const BITS: usize>(value: u64) -> Result<(), TightVecError> {
+    if BITS < 64 && value >> BITS != 0 {
+        return Err(TightVecError::ValueTooWide { value, bits: BITS });
+    }
+
+    Ok(())
+}
+
+/// Panics if a value doesn't fit into `BITS` bits
+impl<const BITS: usize, W: Word> FromIterator<u64> for PackedVec<BITS, W> {
+    #[track_caller]
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+/// Panics if a value doesn't fit into `BITS` bits
+impl<const BITS: usize, W: Word> Extend<u64> for PackedVec<BITS, W> {
+    #[track_caller]
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<const BITS: usize, W: Word> Default for PackedVec<BITS, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const BITS: usize, W: Word> Debug for PackedVec<BITS, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackedVec")
+            .field("bits", &BITS)
+            .field("values", &self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{PackedVec, TightVecError, Word};
+
+    fn round_trip<const BITS: usize, W: Word>() {
+        let values: Vec<u64> = (0..200u64)
+            .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) & PackedVec::<BITS, W>::MAX)
+            .collect();
+
+        let mut v: PackedVec<BITS, W> = values.iter().copied().collect();
+        assert_eq!(v.len(), 200);
+        assert!(v.iter().eq(values.iter().copied()));
+        assert!(v.iter().rev().eq(values.iter().rev().copied()));
+
+        // overwriting must leave the neighbours intact
+        for index in (0..200).step_by(3) {
+            v.set(index, PackedVec::<BITS, W>::MAX - values[index]);
+        }
+        for (index, &value) in values.iter().enumerate() {
+            let expected = if index % 3 == 0 {
+                PackedVec::<BITS, W>::MAX - value
+            } else {
+                value
+            };
+            assert_eq!(v.index(index), expected, "index {index}");
+        }
+
+        assert_eq!(v.pop(), Some(values[199]));
+        assert_eq!(v.len(), 199);
+        assert_eq!(v.as_tightvec().len(), 199 * BITS);
+    }
+
+    #[test]
+    fn widths_and_words() {
+        round_trip::<1, u64>();
+        round_trip::<2, u8>();
+        round_trip::<7, u64>();
+        round_trip::<7, u8>();
+        round_trip::<13, u16>();
+        round_trip::<16, u32>();
+        round_trip::<33, u8>();
+        round_trip::<63, u64>();
+        round_trip::<64, u64>();
+        round_trip::<64, u128>();
+    }
+
+    #[test]
+    fn dial_positions() {
+        let mut dial: PackedVec<7> = PackedVec::with_len_and_value(10, 50);
+
+        dial.set(9, 99);
+
+        assert_eq!(PackedVec::<7>::MAX, 127);
+        assert_eq!(dial.index(9), 99);
+        assert_eq!(dial.get_raw().len(), 2);
+        assert_eq!(
+            format!("{:?}", PackedVec::<3>::from_iter([1, 7, 0])),
+            "PackedVec { bits: 3, values: [1, 7, 0] }"
+        );
+    }
+
+    #[test]
+    fn checked_access() {
+        let mut v: PackedVec<4> = PackedVec::new();
+
+        assert_eq!(
+            v.try_push(16),
+            Err(TightVecError::ValueTooWide { value: 16, bits: 4 })
+        );
+        v.push(15);
+        assert_eq!(
+            v.try_index(1),
+            Err(TightVecError::IndexOutOfBounds { index: 1, len: 1 })
+        );
+        assert_eq!(
+            v.try_set(0, 99),
+            Err(TightVecError::ValueTooWide { value: 99, bits: 4 })
+        );
+        assert_eq!(v.pop(), Some(15));
+        assert_eq!(v.pop(), None);
+    }
+}
@@ -0,0 +1,355 @@
+//! Bulk operations split across scoped threads, for vectors large enough that one core is the
+//! bottleneck.
+//!
+//! The words are cut into contiguous parts at word boundaries, one per worker, and every part is
+//! handled by the same word-level kernels as the sequential operations. Workers are plain
+//! [`std::thread::scope`] threads spawned per call; inputs below one chunk run on the calling
+//! thread without spawning anything.
+
+use std::num::NonZeroUsize;
+use std::ops::{Range, RangeBounds};
+use std::{mem, panic, thread};
+
+use crate::bitops::{BitOp, combine_words};
+use crate::error::or_panic;
+use crate::slice::try_resolve_range;
+use crate::{BitSlice, BitSliceMut, TightVec, TightVecError, Word};
+
+/// How the `par_*` operations of [`TightVec`] split their work
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParallelConfig {
+    /// Maximum number of worker threads
+    pub threads: usize,
+    /// Minimum number of storage words per worker. Inputs are never split into more parts than
+    /// fit this, so small inputs use fewer threads, down to none for a single chunk.
+    pub chunk_words: usize,
+}
+
+impl ParallelConfig {
+    pub fn new(threads: usize, chunk_words: usize) -> Self {
+        Self {
+            threads,
+            chunk_words,
+        }
+    }
+
+    /// bit ranges covering `start..end`, one per worker, split at word boundaries
+    fn split<W: Word>(&self, start: usize, end: usize) -> Vec<Range<usize>> {
+        if start == end {
+            return Vec::new();
+        }
+
+        // partition the words the range touches, so an unaligned start doesn't add a part
+        let (first_word, end_word) = (start / W::BITS, end.div_ceil(W::BITS));
+        let part_words = (end_word - first_word)
+            .div_ceil(self.threads.max(1))
+            .max(self.chunk_words.max(1));
+
+        (first_word..end_word)
+            .step_by(part_words)
+            .map(|word| {
+                let part_end = (word + part_words).min(end_word) * W::BITS;
+                (word * W::BITS).max(start)..part_end.min(end)
+            })
+            .collect()
+    }
+}
+
+/// As many threads as the system offers, with chunks of 16 KiB of `u64` words
+impl Default for ParallelConfig {
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            chunk_words: 2048,
+        }
+    }
+}
+
+impl<W: Word> TightVec<W> {
+    /// Number of set entries
+    pub fn par_count_ones(&self, config: &ParallelConfig) -> usize {
+        self.par_count_ones_in(.., config)
+    }
+
+    /// Number of set entries in the range. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn par_count_ones_in(
+        &self,
+        range: impl RangeBounds<usize>,
+        config: &ParallelConfig,
+    ) -> usize {
+        or_panic(self.try_par_count_ones_in(range, config))
+    }
+
+    pub fn try_par_count_ones_in(
+        &self,
+        range: impl RangeBounds<usize>,
+        config: &ParallelConfig,
+    ) -> Result<usize, TightVecError> {
+        Ok(self
+            .try_par_query(range, config, |part| part.count_ones())?
+            .into_iter()
+            .sum())
+    }
+
+    /// Whether every entry in the range is set. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn par_all_in(&self, range: impl RangeBounds<usize>, config: &ParallelConfig) -> bool {
+        or_panic(self.try_par_all_in(range, config))
+    }
+
+    pub fn try_par_all_in(
+        &self,
+        range: impl RangeBounds<usize>,
+        config: &ParallelConfig,
+    ) -> Result<bool, TightVecError> {
+        Ok(self
+            .try_par_query(range, config, |part| part.all())?
+            .into_iter()
+            .all(|all| all))
+    }
+
+    /// Whether any entry in the range is set. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn par_any_in(&self, range: impl RangeBounds<usize>, config: &ParallelConfig) -> bool {
+        or_panic(self.try_par_any_in(range, config))
+    }
+
+    pub fn try_par_any_in(
+        &self,
+        range: impl RangeBounds<usize>,
+        config: &ParallelConfig,
+    ) -> Result<bool, TightVecError> {
+        Ok(self
+            .try_par_query(range, config, |part| part.any())?
+            .into_iter()
+            .any(|any| any))
+    }
+
+    /// Sets every entry in the range to `value`. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn par_fill(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: bool,
+        config: &ParallelConfig,
+    ) {
+        or_panic(self.try_par_fill(range, value, config))
+    }
+
+    pub fn try_par_fill(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: bool,
+        config: &ParallelConfig,
+    ) -> Result<(), TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        let mut parts = Vec::new();
+        let mut rest = &mut self.inner[..];
+        let mut first_word = 0;
+
+        // inner part boundaries lie on word boundaries, so the parts own disjoint words
+        for part in config.split::<W>(start, end) {
+            let end_word = part.end.div_ceil(W::BITS);
+            let (words, tail) = mem::take(&mut rest).split_at_mut(end_word - first_word);
+
+            parts.push(BitSliceMut::new(
+                words,
+                part.start - first_word * W::BITS,
+                part.len(),
+            ));
+            rest = tail;
+            first_word = end_word;
+        }
+
+        run(parts, |mut part| part.fill(value));
+
+        Ok(())
+    }
+
+    /// Entry-wise `self &= other`. Panics if the lengths differ.
+    #[track_caller]
+    pub fn par_and(&mut self, other: &TightVec<W>, config: &ParallelConfig) {
+        self.par_combine(BitOp::And, other, config);
+    }
+
+    /// Entry-wise `self |= other`. Panics if the lengths differ.
+    #[track_caller]
+    pub fn par_or(&mut self, other: &TightVec<W>, config: &ParallelConfig) {
+        self.par_combine(BitOp::Or, other, config);
+    }
+
+    /// Entry-wise `self ^= other`. Panics if the lengths differ.
+    #[track_caller]
+    pub fn par_xor(&mut self, other: &TightVec<W>, config: &ParallelConfig) {
+        self.par_combine(BitOp::Xor, other, config);
+    }
+
+    fn try_par_query<T: Send>(
+        &self,
+        range: impl RangeBounds<usize>,
+        config: &ParallelConfig,
+        query: impl Fn(BitSlice<'_, W>) -> T + Sync,
+    ) -> Result<Vec<T>, TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        let parts = config
+            .split::<W>(start, end)
+            .into_iter()
+            .map(|part| self.slice(part))
+            .collect();
+
+        Ok(run(parts, query))
+    }
+
+    #[track_caller]
+    fn par_combine(&mut self, op: BitOp, other: &TightVec<W>, config: &ParallelConfig) {
+        assert_eq!(
+            self.len, other.len,
+            "bitwise operations need vectors of the same length"
+        );
+
+        let part_words = config
+            .split::<W>(0, self.len)
+            .first()
+            .map_or(1, |part| part.len().div_ceil(W::BITS));
+
+        let parts = self
+            .inner
+            .chunks_mut(part_words)
+            .zip(other.inner.chunks(part_words))
+            .collect();
+
+        run(parts, |(dst, src)| combine_words(op, dst, src));
+    }
+}
+
+/// applies `f` to every part, one scoped thread per part unless there's only one.
+/// Panics of workers are passed on to the caller.
+fn run<P: Send, T: Send>(parts: Vec<P>, f: impl Fn(P) -> T + Sync) -> Vec<T> {
+    if parts.len() <= 1 {
+        return parts.into_iter().map(f).collect();
+    }
+
+    thread::scope(|scope| {
+        let f = &f;
+        let handles: Vec<_> = parts
+            .into_iter()
+            .map(|part| scope.spawn(move || f(part)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::pattern;
+    use crate::{ParallelConfig, TightVec, TightVecError, Word};
+
+    fn matches_sequential<W: Word>() {
+        let configs = [
+            ParallelConfig::new(1, 1),
+            ParallelConfig::new(4, 1),
+            ParallelConfig::new(3, 5),
+            ParallelConfig::new(8, 1000),
+        ];
+
+        for config in &configs {
+            for len in [0, 1, 70, 1000, 5003] {
+                let a = pattern::<W>(len, 3);
+                let b = pattern::<W>(len, 7);
+
+                assert_eq!(a.par_count_ones(config), a.as_bitslice().count_ones());
+
+                for (start, end) in [(0, len), (len / 3, len), (len / 5, (len / 2 + 1).min(len))] {
+                    let expected = a.slice(start..end);
+
+                    assert_eq!(
+                        a.par_count_ones_in(start..end, config),
+                        expected.count_ones()
+                    );
+                    assert_eq!(a.par_all_in(start..end, config), expected.all());
+                    assert_eq!(a.par_any_in(start..end, config), expected.any());
+
+                    let (mut fast, mut slow) = (a.clone(), a.clone());
+                    fast.par_fill(start..end, true, config);
+                    slow.slice_mut(start..end).fill(true);
+                    assert_eq!(fast, slow);
+                    assert!(fast.par_all_in(start..end, config));
+                }
+
+                let mut and = a.clone();
+                and.par_and(&b, config);
+                assert_eq!(and, &a & &b);
+
+                let mut or = a.clone();
+                or.par_or(&b, config);
+                assert_eq!(or, &a | &b);
+
+                let mut xor = a.clone();
+                xor.par_xor(&b, config);
+                assert_eq!(xor, &a ^ &b);
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        matches_sequential::<u8>();
+        matches_sequential::<u64>();
+        matches_sequential::<u128>();
+    }
+
+    #[test]
+    fn splits_at_word_boundaries() {
+        let config = ParallelConfig::new(4, 2);
+
+        assert_eq!(
+            config.split::<u64>(10, 1000),
+            [10..256, 256..512, 512..768, 768..1000]
+        );
+        assert_eq!(config.split::<u64>(10, 100).len(), 1);
+        assert_eq!(config.split::<u64>(10, 100)[0], 10..100);
+        assert!(config.split::<u64>(5, 5).is_empty());
+    }
+
+    #[test]
+    fn unaligned_start_adds_no_part() {
+        let config = ParallelConfig::new(4, 1);
+
+        assert_eq!(config.split::<u64>(60, 316), [60..128, 128..256, 256..316]);
+        assert_eq!(config.split::<u8>(3, 29), [3..8, 8..16, 16..24, 24..29]);
+    }
+
+    #[test]
+    fn checked_range_operations() {
+        let config = ParallelConfig::default();
+        let mut v: TightVec = TightVec::with_len_and_value(10, false);
+        let error = TightVecError::RangeOutOfBounds { end: 11, len: 10 };
+
+        assert_eq!(v.try_par_fill(5..11, true, &config), Err(error));
+        assert_eq!(v, TightVec::with_len_and_value(10, false));
+        assert_eq!(v.try_par_count_ones_in(5..11, &config), Err(error));
+        assert_eq!(v.try_par_all_in(5..11, &config), Err(error));
+        assert_eq!(v.try_par_any_in(5..11, &config), Err(error));
+        assert_eq!(v.try_par_count_ones_in(5..10, &config), Ok(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn length_mismatch() {
+        let mut a: TightVec = TightVec::with_len_and_value(3, true);
+        let b: TightVec = TightVec::with_len_and_value(4, true);
+
+        a.par_or(&b, &ParallelConfig::default());
+    }
+}
@@ -0,0 +1,336 @@
+//! Netpbm images of bit grids: PBM (one bit per pixel) in both encodings, and PGM (grayscale) for
+//! downsampled overviews of grids too large to look at pixel by pixel.
+//!
+//! Row `0` is the top of the image and set cells are black, as in PBM itself.
+
+use std::io::{self, BufWriter, Read, Write};
+
+use crate::grid::BitRows;
+use crate::{BitGrid, TightVecError, Word};
+
+/// Encoding of a PBM image
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PbmFormat {
+    /// `P1`: ASCII `0`s and `1`s, readable in a text editor
+    Plain,
+    /// `P4`: eight pixels per byte
+    Binary,
+}
+
+/// longest line the plain format allows
+const PLAIN_LINE_LEN: usize = 70;
+
+/// Writes the rows as a PBM image, `cols` pixels wide and `rows` pixels high
+pub fn write_pbm<R: BitRows + ?Sized>(
+    grid: &R,
+    writer: impl Write,
+    format: PbmFormat,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    let magic = match format {
+        PbmFormat::Plain => "P1",
+        PbmFormat::Binary => "P4",
+    };
+    writeln!(writer, "{magic}\n{} {}", grid.cols(), grid.rows())?;
+
+    for row_index in 0..grid.rows() {
+        let row = grid.row(row_index);
+
+        match format {
+            PbmFormat::Plain => {
+                let digits: Vec<u8> = row.iter().map(|bit| b'0' + bit as u8).collect();
+                for line in digits.chunks(PLAIN_LINE_LEN) {
+                    writer.write_all(line)?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+            PbmFormat::Binary => {
+                for chunk_index in 0..row.chunk_count() {
+                    let (chunk, bits) = row.chunk(chunk_index);
+
+                    // PBM puts the first pixel into the most significant bit
+                    for byte in 0..bits.div_ceil(8) {
+                        writer
+                            .write_all(&[((chunk >> (byte * 8)).to_u64() as u8).reverse_bits()])?;
+                    }
+                }
+            }
+        }
+    }
+
+    writer.flush()
+}
+
+/// Writes a binary PGM image with one pixel per `block` × `block` cells, shaded by the share of
+/// set cells in the block: black if all are set, white if none are. Blocks at the right and bottom
+/// edges may be smaller. Panics if `block` is zero.
+#[track_caller]
+pub fn write_pgm<R: BitRows + ?Sized>(
+    grid: &R,
+    writer: impl Write,
+    block: usize,
+) -> io::Result<()> {
+    assert!(block > 0, "blocks must be at least one cell wide");
+
+    let (rows, cols) = (grid.rows(), grid.cols());
+    let width = cols.div_ceil(block);
+
+    let mut writer = BufWriter::new(writer);
+    writeln!(writer, "P5\n{width} {}\n255", rows.div_ceil(block))?;
+
+    let mut counts = vec![0; width];
+    let mut pixels = vec![0; width];
+
+    for first_row in (0..rows).step_by(block) {
+        let block_rows = (rows - first_row).min(block);
+        counts.fill(0);
+
+        for row_index in first_row..first_row + block_rows {
+            let row = grid.row(row_index);
+
+            for (block_col, count) in counts.iter_mut().enumerate() {
+                let first_col = block_col * block;
+                *count += row
+                    .slice(first_col..(first_col + block).min(cols))
+                    .count_ones();
+            }
+        }
+
+        for (block_col, (pixel, &count)) in pixels.iter_mut().zip(&counts).enumerate() {
+            let cells = block_rows * (cols - block_col * block).min(block);
+            *pixel = 255 - (count * 255 + cells / 2) / cells;
+        }
+
+        writer.write_all(&pixels.iter().map(|&pixel| pixel as u8).collect::<Vec<_>>())?;
+    }
+
+    writer.flush()
+}
+
+/// Reads a PBM image in either encoding into a grid, one row per line of pixels.
+/// Fails with [`io::ErrorKind::InvalidData`] if it isn't a valid PBM image.
+pub fn read_pbm<W: Word>(mut reader: impl Read) -> io::Result<BitGrid<W>> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let format = match bytes.get(..2) {
+        Some(b"P1") => PbmFormat::Plain,
+        Some(b"P4") => PbmFormat::Binary,
+        _ => return Err(invalid_data("not a PBM image")),
+    };
+
+    let mut position = 2;
+    let cols = header_number(&bytes, &mut position)?;
+    let rows = header_number(&bytes, &mut position)?;
+    let cells = rows
+        .checked_mul(cols)
+        .ok_or_else(|| invalid_data("the image size in the PBM header overflows usize"))?;
+
+    // the header alone mustn't make us allocate, so the data is checked against the size first
+    match format {
+        PbmFormat::Plain => {
+            let digits = bytes[position..]
+                .iter()
+                .enumerate()
+                .filter(|(_, byte)| !byte.is_ascii_whitespace());
+
+            if digits.clone().count() < cells {
+                return Err(truncated());
+            }
+
+            let mut grid = BitGrid::new(rows, cols);
+
+            for (cell, (offset, &byte)) in digits.take(cells).enumerate() {
+                match byte {
+                    b'0' => {}
+                    b'1' => grid.set(cell / cols, cell % cols, true),
+                    _ => {
+                        return Err(invalid_data(TightVecError::InvalidCharacter {
+                            character: byte as char,
+                            position: position + offset,
+                        }));
+                    }
+                }
+            }
+
+            Ok(grid)
+        }
+        PbmFormat::Binary => {
+            // a single whitespace byte separates the header from the raster, whose size can't
+            // overflow as it's at most `cells`
+            let row_bytes = cols.div_ceil(8);
+            let raster = bytes
+                .get(position + 1..)
+                .filter(|raster| raster.len() >= rows * row_bytes)
+                .ok_or_else(truncated)?;
+
+            let mut grid = BitGrid::new(rows, cols);
+
+            // rows without columns have no bytes to cut the raster into
+            if row_bytes == 0 {
+                return Ok(grid);
+            }
+
+            for (row_index, line) in raster.chunks_exact(row_bytes).take(rows).enumerate() {
+                let mut row = grid.row_mut(row_index);
+
+                for (byte_index, &byte) in line.iter().enumerate() {
+                    let bits = (cols - byte_index * 8).min(8);
+                    row.store_bits(
+                        byte_index * 8,
+                        W::from_u64(byte.reverse_bits() as u64),
+                        bits,
+                    );
+                }
+            }
+
+            Ok(grid)
+        }
+    }
+}
+
+/// parses the next header number, skipping whitespace and `#` comments before it
+fn header_number(bytes: &[u8], position: &mut usize) -> io::Result<usize> {
+    loop {
+        match bytes.get(*position) {
+            Some(byte) if byte.is_ascii_whitespace() => *position += 1,
+            Some(b'#') => {
+                while bytes.get(*position).is_some_and(|&byte| byte != b'\n') {
+                    *position += 1;
+                }
+            }
+            Some(_) => break,
+            None => return Err(truncated()),
+        }
+    }
+
+    let start = *position;
+    while bytes.get(*position).is_some_and(u8::is_ascii_digit) {
+        *position += 1;
+    }
+
+    std::str::from_utf8(&bytes[start..*position])
+        .ok()
+        .and_then(|digits| digits.parse().ok())
+        .ok_or_else(|| invalid_data("invalid image size in the PBM header"))
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "the PBM image is truncated")
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+
+    use crate::test_util::grid_pattern;
+    use crate::{BitGrid, PbmFormat, TightVec, Word, read_pbm, write_pbm, write_pgm};
+
+    fn round_trips<W: Word>() {
+        for (rows, cols) in [(0, 0), (1, 1), (3, 8), (5, 13), (2, 150), (40, 71)] {
+            let grid = grid_pattern::<W>(rows, cols);
+
+            for format in [PbmFormat::Plain, PbmFormat::Binary] {
+                let mut image = Vec::new();
+                write_pbm(&grid, &mut image, format).unwrap();
+
+                assert_eq!(read_pbm::<W>(image.as_slice()).unwrap(), grid);
+            }
+        }
+    }
+
+    #[test]
+    fn pbm_round_trip() {
+        round_trips::<u8>();
+        round_trips::<u64>();
+        round_trips::<u128>();
+    }
+
+    #[test]
+    fn pbm_layout() {
+        let grid: BitGrid<u16> = "#........#\n.#........".parse().unwrap();
+        let rows: Vec<TightVec<u16>> = (0..2).map(|row| grid.row(row).to_tightvec()).collect();
+
+        let mut plain = Vec::new();
+        write_pbm(&rows, &mut plain, PbmFormat::Plain).unwrap();
+        assert_eq!(plain, b"P1\n10 2\n1000000001\n0100000000\n");
+
+        let mut binary = Vec::new();
+        write_pbm(&grid, &mut binary, PbmFormat::Binary).unwrap();
+        assert_eq!(binary, b"P4\n10 2\n\x80\x40\x40\x00");
+
+        let mut wide = Vec::new();
+        write_pbm(
+            &BitGrid::<u8>::with_value(1, 75, true),
+            &mut wide,
+            PbmFormat::Plain,
+        )
+        .unwrap();
+        assert_eq!(
+            wide,
+            format!("P1\n75 1\n{}\n11111\n", "1".repeat(70)).as_bytes()
+        );
+    }
+
+    #[test]
+    fn reads_comments_and_spacing() {
+        let image = b"P1\n# a comment\n3 # width\n2\n1 0 1\n0 1 0\n";
+        let grid: BitGrid = read_pbm(image.as_slice()).unwrap();
+
+        assert_eq!(grid.to_string(), "101\n010");
+
+        // padding bits at the end of each row are ignored
+        let grid: BitGrid = read_pbm(b"P4 3 2 \xbf\xff".as_slice()).unwrap();
+        assert_eq!(grid.to_string(), "101\n111");
+    }
+
+    #[test]
+    fn reads_images_without_columns() {
+        for image in [&b"P4 0 5 \xff"[..], b"P4 0 5 ", b"P1 0 5\n"] {
+            assert_eq!(read_pbm::<u64>(image).unwrap(), BitGrid::new(5, 0));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_images() {
+        for (image, kind) in [
+            (&b"P2\n1 1\n1"[..], ErrorKind::InvalidData),
+            (b"P1\nx 1\n1", ErrorKind::InvalidData),
+            (b"P1\n2 1\n12", ErrorKind::InvalidData),
+            (b"P1\n2 2\n10", ErrorKind::UnexpectedEof),
+            (b"P4\n9 2\n\xff\xff\xff", ErrorKind::UnexpectedEof),
+            (b"P4\n9", ErrorKind::UnexpectedEof),
+            // sizes far beyond the data fail before anything is allocated
+            (b"P4 4000000000 4000000000 x", ErrorKind::UnexpectedEof),
+            (b"P4 1 18446744073709551615 x", ErrorKind::UnexpectedEof),
+            (b"P1 4000000000 4000000000 1", ErrorKind::UnexpectedEof),
+            (
+                b"P4 18446744073709551615 18446744073709551615 x",
+                ErrorKind::InvalidData,
+            ),
+            (b"P1 18446744073709551615 2 1", ErrorKind::InvalidData),
+        ] {
+            assert_eq!(read_pbm::<u64>(image).unwrap_err().kind(), kind);
+        }
+    }
+
+    #[test]
+    fn pgm_downsamples() {
+        let mut grid: BitGrid = BitGrid::new(5, 7);
+        grid.fill_rect(0..4, 0..4, true);
+        grid.fill_rect(4..5, 4..5, true);
+
+        let mut image = Vec::new();
+        write_pgm(&grid, &mut image, 4).unwrap();
+
+        assert_eq!(image, b"P5\n2 2\n255\n\x00\xff\xff\xaa");
+
+        let mut image = Vec::new();
+        write_pgm(&grid, &mut image, 1).unwrap();
+        assert_eq!(image.len(), "P5\n7 5\n255\n".len() + 35);
+    }
+}
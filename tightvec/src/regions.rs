@@ -0,0 +1,551 @@
+//! Flood fill and connected-component labeling.
+//!
+//! Both work on runs: maximal horizontal stretches of equal cells, found a word at a time. A run
+//! is connected to a run in the row above or below if their columns overlap, or, with
+//! [`Connectivity::Eight`], if they touch diagonally.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::error::or_panic;
+use crate::grid::BitRows;
+use crate::slice::check_index;
+use crate::{BitGrid, TightVecError, Word};
+
+/// Which neighbours of a cell count as connected to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// The cells above, below, left and right
+    Four,
+    /// The four orthogonal and the four diagonal neighbours
+    Eight,
+}
+
+impl Connectivity {
+    /// how many columns a run reaches past its ends into the neighbouring rows
+    fn reach(self) -> usize {
+        match self {
+            Connectivity::Four => 0,
+            Connectivity::Eight => 1,
+        }
+    }
+}
+
+/// The connected components of the set cells of a grid.
+///
+/// Components are labeled `0..count()` in the order their first cell appears in row-major order.
+/// Stores one entry per run of set cells rather than one per cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Components {
+    rows: usize,
+    cols: usize,
+    count: usize,
+    runs: Vec<Run>,
+    /// index of the first run of every row, plus the total number of runs
+    row_starts: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Run {
+    cols: Range<usize>,
+    label: usize,
+}
+
+impl<W: Word> BitGrid<W> {
+    /// The cells connected to `(row, col)` that have the same value as it, the cell itself included.
+    /// Panics if the cell is out of bounds.
+    #[track_caller]
+    pub fn region(&self, row: usize, col: usize, connectivity: Connectivity) -> BitGrid<W> {
+        or_panic(self.try_region(row, col, connectivity))
+    }
+
+    pub fn try_region(
+        &self,
+        row: usize,
+        col: usize,
+        connectivity: Connectivity,
+    ) -> Result<BitGrid<W>, TightVecError> {
+        let value = self.try_get(row, col)?;
+
+        let mut flood = Flood::new(cells_equal_to(self, value), connectivity);
+        flood.seed(row, col);
+
+        Ok(flood.finish(self, value))
+    }
+
+    /// Sets every unset cell connected to `(row, col)`, like a paint bucket.
+    /// Does nothing if the cell is already set. Panics if the cell is out of bounds.
+    #[track_caller]
+    pub fn flood_fill(&mut self, row: usize, col: usize, connectivity: Connectivity) {
+        or_panic(self.try_flood_fill(row, col, connectivity))
+    }
+
+    pub fn try_flood_fill(
+        &mut self,
+        row: usize,
+        col: usize,
+        connectivity: Connectivity,
+    ) -> Result<(), TightVecError> {
+        if self.try_get(row, col)? {
+            return Ok(());
+        }
+
+        let mut flood = Flood::new(cells_equal_to(self, false), connectivity);
+        flood.seed(row, col);
+        flood.run();
+
+        // everything but the unset cells that weren't reached
+        for (word, remaining) in self.words_mut().iter_mut().zip(flood.remaining.get_raw()) {
+            *word = !*remaining;
+        }
+        self.clear_padding();
+
+        Ok(())
+    }
+
+    /// The unset cells that are connected to the border of the grid, i.e. everything outside of
+    /// the shapes drawn by the set cells
+    pub fn exterior(&self, connectivity: Connectivity) -> BitGrid<W> {
+        let mut flood = Flood::new(cells_equal_to(self, false), connectivity);
+        flood.seed_border();
+
+        flood.finish(self, false)
+    }
+
+    /// Sets every unset cell that isn't connected to the border of the grid, filling the inside
+    /// of every closed outline
+    pub fn fill_enclosed(&mut self, connectivity: Connectivity) {
+        let mut flood = Flood::new(cells_equal_to(self, false), connectivity);
+        flood.seed_border();
+        flood.run();
+
+        for (word, enclosed) in self.words_mut().iter_mut().zip(flood.remaining.get_raw()) {
+            *word |= *enclosed;
+        }
+    }
+}
+
+impl Components {
+    /// Labels the connected components of the set cells of `grid`
+    pub fn new<R: BitRows + ?Sized>(grid: &R, connectivity: Connectivity) -> Self {
+        let reach = connectivity.reach();
+
+        let mut runs = Vec::new();
+        let mut parents = Vec::new();
+        let mut row_starts = vec![0];
+
+        for row in 0..grid.rows() {
+            let bits = grid.row(row);
+            let previous = match row {
+                0 => 0..0,
+                _ => row_starts[row - 1]..row_starts[row],
+            };
+            let mut above = previous.start;
+            let mut from = 0;
+
+            while let Some(start) = bits.next_index_of(true, from) {
+                let end = bits.next_index_of(false, start).unwrap_or(bits.len());
+                let index = runs.len();
+
+                runs.push(start..end);
+                parents.push(index);
+
+                // runs of the previous row that end left of this one can't touch any later run either
+                while above < previous.end && runs[above].end + reach <= start {
+                    above += 1;
+                }
+
+                let touching = runs[above..previous.end]
+                    .iter()
+                    .take_while(|other| other.start < end + reach)
+                    .count();
+
+                for other in above..above + touching {
+                    union(&mut parents, other, index);
+                }
+
+                from = end;
+            }
+
+            row_starts.push(runs.len());
+        }
+
+        let mut labels = vec![usize::MAX; runs.len()];
+        let mut count = 0;
+
+        let runs = runs
+            .into_iter()
+            .enumerate()
+            .map(|(index, cols)| {
+                let root = find(&mut parents, index);
+
+                if labels[root] == usize::MAX {
+                    labels[root] = count;
+                    count += 1;
+                }
+
+                Run {
+                    cols,
+                    label: labels[root],
+                }
+            })
+            .collect();
+
+        Self {
+            rows: grid.rows(),
+            cols: grid.cols(),
+            count,
+            runs,
+            row_starts,
+        }
+    }
+
+    /// Number of components
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Label of the component containing the cell, `None` for an unset cell.
+    /// Panics if the cell is out of bounds.
+    #[track_caller]
+    pub fn label(&self, row: usize, col: usize) -> Option<usize> {
+        or_panic(self.try_label(row, col))
+    }
+
+    pub fn try_label(&self, row: usize, col: usize) -> Result<Option<usize>, TightVecError> {
+        check_index(row, self.rows)?;
+        check_index(col, self.cols)?;
+
+        let row_runs = &self.runs[self.row_starts[row]..self.row_starts[row + 1]];
+        let index = row_runs.partition_point(|run| run.cols.end <= col);
+
+        Ok(row_runs
+            .get(index)
+            .filter(|run| run.cols.contains(&col))
+            .map(|run| run.label))
+    }
+
+    /// Number of cells of every component, indexed by label
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.count];
+
+        for run in &self.runs {
+            sizes[run.label] += run.cols.len();
+        }
+
+        sizes
+    }
+
+    /// The cells of one component as a grid of the original size
+    pub fn mask<W: Word>(&self, label: usize) -> BitGrid<W> {
+        let mut grid = BitGrid::new(self.rows, self.cols);
+
+        for (row, runs) in self.row_starts.windows(2).enumerate() {
+            for run in &self.runs[runs[0]..runs[1]] {
+                if run.label == label {
+                    grid.fill_rect(row..=row, run.cols.clone(), true);
+                }
+            }
+        }
+
+        grid
+    }
+}
+
+/// scanline flood fill, moving runs out of `remaining` as they are reached
+struct Flood<W: Word> {
+    /// cells that may still be reached
+    remaining: BitGrid<W>,
+    /// reached runs whose neighbouring rows haven't been searched yet
+    pending: Vec<(usize, Range<usize>)>,
+    reach: usize,
+}
+
+impl<W: Word> Flood<W> {
+    fn new(passable: BitGrid<W>, connectivity: Connectivity) -> Self {
+        Self {
+            remaining: passable,
+            pending: Vec::new(),
+            reach: connectivity.reach(),
+        }
+    }
+
+    /// reaches the run through `(row, col)`, if that cell can still be reached.
+    /// Returns the column after the run, or after the cell if it can't be reached.
+    fn seed(&mut self, row: usize, col: usize) -> usize {
+        if !self.remaining.get(row, col) {
+            return col + 1;
+        }
+
+        let bits = self.remaining.row(row);
+        let start = bits.prev_index_of(false, col).map_or(0, |index| index + 1);
+        let end = bits.next_index_of(false, col).unwrap_or(bits.len());
+
+        self.take(row, start..end);
+
+        end
+    }
+
+    /// reaches every cell on the border of the grid
+    fn seed_border(&mut self) {
+        let (rows, cols) = (self.remaining.rows(), self.remaining.cols());
+
+        if rows == 0 || cols == 0 {
+            return;
+        }
+
+        // seeding only clears the seeded run, so each row is scanned once, left to right
+        for row in [0, rows - 1] {
+            let mut from = 0;
+
+            while let Some(col) = self.remaining.row(row).next_index_of(true, from) {
+                from = self.seed(row, col);
+            }
+        }
+
+        for row in 0..rows {
+            self.seed(row, 0);
+            self.seed(row, cols - 1);
+        }
+    }
+
+    fn take(&mut self, row: usize, cols: Range<usize>) {
+        self.remaining.fill_rect(row..=row, cols.clone(), false);
+        self.pending.push((row, cols));
+    }
+
+    /// reaches everything connected to the seeded runs
+    fn run(&mut self) {
+        let rows = self.remaining.rows();
+        let cols = self.remaining.cols();
+
+        while let Some((row, reached)) = self.pending.pop() {
+            let search_start = reached.start.saturating_sub(self.reach);
+            let search_end = (reached.end + self.reach).min(cols);
+
+            for neighbour in [
+                row.checked_sub(1),
+                Some(row + 1).filter(|&next| next < rows),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                let mut from = search_start;
+
+                while let Some(col) = self.remaining.row(neighbour).next_index_of(true, from)
+                    && col < search_end
+                {
+                    let bits = self.remaining.row(neighbour);
+                    let start = bits.prev_index_of(false, col).map_or(0, |index| index + 1);
+                    let end = bits.next_index_of(false, col).unwrap_or(cols);
+
+                    self.take(neighbour, start..end);
+                    from = end;
+                }
+            }
+        }
+    }
+
+    /// the reached cells of `grid`, which was flooded through its cells equal to `value`.
+    /// Reuses the flood's own grid for the result.
+    fn finish(mut self, grid: &BitGrid<W>, value: bool) -> BitGrid<W> {
+        self.run();
+
+        let invert = W::fill_value(!value);
+
+        for (remaining, &word) in self.remaining.words_mut().iter_mut().zip(grid.get_raw()) {
+            *remaining = (word ^ invert) & !*remaining;
+        }
+        self.remaining.clear_padding();
+
+        self.remaining
+    }
+}
+
+/// copy of `grid` with the cells equal to `value` set and all others unset
+fn cells_equal_to<W: Word>(grid: &BitGrid<W>, value: bool) -> BitGrid<W> {
+    let mut cells = grid.clone();
+
+    if !value {
+        for word in cells.words_mut() {
+            *word = !*word;
+        }
+        cells.clear_padding();
+    }
+
+    cells
+}
+
+/// root of `index` in the union-find forest, halving the path on the way
+fn find(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+
+    index
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+
+    parents[a.max(b)] = a.min(b);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{BitGrid, Components, Connectivity, TightVec, TightVecError};
+
+    fn grid(text: &str) -> BitGrid {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn region_of_set_and_unset_cells() {
+        let g = grid(
+            "##....\n\
+             .#..#.\n\
+             ..#.#.\n\
+             ....##",
+        );
+
+        assert_eq!(
+            g.region(0, 0, Connectivity::Four),
+            grid("##....\n.#....\n......\n......")
+        );
+        assert_eq!(
+            g.region(0, 0, Connectivity::Eight),
+            grid("##....\n.#....\n..#...\n......")
+        );
+        assert_eq!(g.region(1, 3, Connectivity::Four).count_set(.., ..), 16);
+        assert_eq!(
+            g.try_region(4, 0, Connectivity::Four),
+            Err(TightVecError::IndexOutOfBounds { index: 4, len: 4 })
+        );
+    }
+
+    #[test]
+    fn flood_fill_stops_at_walls() {
+        let mut g = grid(
+            "#####.\n\
+             #...#.\n\
+             #..##.\n\
+             ####..",
+        );
+
+        g.flood_fill(1, 1, Connectivity::Four);
+        assert_eq!(g, grid("#####.\n#####.\n#####.\n####.."));
+
+        g.flood_fill(0, 0, Connectivity::Four);
+        assert_eq!(g.count_set(.., ..), 19);
+    }
+
+    #[test]
+    fn diagonal_gaps_leak_with_eight_connectivity() {
+        let outline = grid(
+            "......\n\
+             .##...\n\
+             .#.#..\n\
+             ..##..\n\
+             ......",
+        );
+
+        let mut four = outline.clone();
+        four.fill_enclosed(Connectivity::Four);
+        assert!(four.get(2, 2));
+
+        let mut eight = outline.clone();
+        eight.fill_enclosed(Connectivity::Eight);
+        assert_eq!(eight, outline);
+
+        assert_eq!(
+            outline.exterior(Connectivity::Four).count_set(.., ..),
+            30 - 6 - 1
+        );
+    }
+
+    #[test]
+    fn fill_enclosed_across_words() {
+        let mut g: BitGrid<u8> = BitGrid::new(12, 150);
+        for (rows, cols) in [
+            (2..=2, 5..=140),
+            (9..=9, 5..=140),
+            (2..=9, 5..=5),
+            (2..=9, 140..=140),
+        ] {
+            g.fill_rect(rows, cols, true);
+        }
+
+        let exterior = g.exterior(Connectivity::Four);
+        g.fill_enclosed(Connectivity::Four);
+
+        assert!(g.all_set(2..=9, 5..=140));
+        assert_eq!(g.count_set(.., ..), 8 * 136);
+        assert_eq!(exterior.count_set(.., ..), 12 * 150 - 8 * 136);
+    }
+
+    #[test]
+    fn border_with_many_runs() {
+        // every other border cell is set, so the border rows consist of many short runs
+        let mut g: BitGrid<u8> = BitGrid::new(5, 101);
+        for col in (0..101).step_by(2) {
+            g.set(0, col, true);
+            g.set(4, col, true);
+        }
+
+        let exterior = g.exterior(Connectivity::Four);
+        assert_eq!(exterior.count_set(.., ..), 5 * 101 - 2 * 51);
+
+        let mut filled = g.clone();
+        filled.fill_enclosed(Connectivity::Four);
+        assert_eq!(filled, g);
+
+        filled.flood_fill(2, 50, Connectivity::Four);
+        assert_eq!(filled.count_set(.., ..), 5 * 101);
+    }
+
+    #[test]
+    fn labels_components() {
+        let g = grid(
+            "##..#\n\
+             ..#.#\n\
+             .##..\n\
+             #...#",
+        );
+
+        let four = Components::new(&g, Connectivity::Four);
+        assert_eq!(four.count(), 5);
+        assert_eq!(four.label(0, 0), Some(0));
+        assert_eq!(four.label(1, 4), Some(1));
+        assert_eq!(four.label(2, 1), Some(2));
+        assert_eq!(four.label(3, 4), Some(4));
+        assert_eq!(four.label(0, 2), None);
+        assert_eq!(four.sizes(), [2, 2, 3, 1, 1]);
+
+        let eight = Components::new(&g, Connectivity::Eight);
+        assert_eq!(eight.count(), 3);
+        assert_eq!(eight.label(3, 0), Some(0));
+        assert_eq!(eight.sizes(), [6, 2, 1]);
+        assert_eq!(eight.mask::<u64>(0), grid("##...\n..#..\n.##..\n#...."));
+        assert_eq!(
+            eight.try_label(0, 5),
+            Err(TightVecError::IndexOutOfBounds { index: 5, len: 5 })
+        );
+    }
+
+    #[test]
+    fn labels_merge_late() {
+        // the two arms only meet in the last row, after both got their own run labels
+        let rows: Vec<TightVec> = ["1000001", "1000001", "1111111"]
+            .iter()
+            .map(|row| row.parse().unwrap())
+            .collect();
+
+        let components = Components::new(&rows, Connectivity::Four);
+
+        assert_eq!(components.count(), 1);
+        assert_eq!(components.label(0, 6), Some(0));
+        assert_eq!(components.sizes(), [11]);
+    }
+}
@@ -0,0 +1,349 @@
+use alloc::vec::Vec;
+use core::iter;
+use core::ops::{Range, RangeBounds};
+
+use crate::container::{BitContainer, BitContainerMut};
+use crate::error::or_panic;
+use crate::slice::{check_index, try_resolve_range};
+use crate::{BitSlice, TightVec, TightVecError, Word};
+
+/// A run-length encoded bit vector: stores the ranges of set entries instead of the entries.
+///
+/// Memory grows with the number of runs, not with the length, so long and mostly uniform vectors
+/// (e.g. rows of a huge, sparsely drawn field) stay small. Lookups are a binary search over the
+/// runs, counting a range visits every run inside it. Implements the same [`BitContainer`] traits
+/// as [`TightVec`], so callers can switch between the two.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RunVec {
+    /// sorted, non-empty ranges of set entries; neither overlapping nor touching
+    runs: Vec<Range<usize>>,
+    len: usize,
+}
+
+impl RunVec {
+    /// Creates a vector of `len` unset entries
+    pub fn new(len: usize) -> Self {
+        Self::with_len_and_value(len, false)
+    }
+
+    pub fn with_len_and_value(len: usize, value: bool) -> Self {
+        let mut runs = Vec::new();
+
+        if value && len > 0 {
+            runs.push(0..len);
+        }
+
+        Self { runs, len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn get(&self, index: usize) -> bool {
+        or_panic(self.try_get(index))
+    }
+
+    pub fn try_get(&self, index: usize) -> Result<bool, TightVecError> {
+        check_index(index, self.len)?;
+
+        let run = self.runs.partition_point(|run| run.end <= index);
+
+        Ok(self.runs.get(run).is_some_and(|run| run.start <= index))
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: bool) {
+        or_panic(self.try_set(index, value))
+    }
+
+    pub fn try_set(&mut self, index: usize, value: bool) -> Result<(), TightVecError> {
+        check_index(index, self.len)?;
+
+        self.fill_resolved(index, index + 1, value);
+
+        Ok(())
+    }
+
+    /// Appends an entry, extending the last run if possible
+    pub fn push(&mut self, value: bool) {
+        self.len += 1;
+
+        if value {
+            self.fill_resolved(self.len - 1, self.len, true);
+        }
+    }
+
+    /// Sets every entry in the range to `value`. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        or_panic(self.try_fill_range(range, value))
+    }
+
+    pub fn try_fill_range(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: bool,
+    ) -> Result<(), TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        self.fill_resolved(start, end, value);
+
+        Ok(())
+    }
+
+    /// Number of set entries in the range. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        or_panic(self.try_count_ones_in(range))
+    }
+
+    pub fn try_count_ones_in(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<usize, TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        Ok(self
+            .overlapping(start, end)
+            .map(|run| run.end.min(end) - run.start.max(start))
+            .sum())
+    }
+
+    /// Number of runs of set entries
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// The maximal ranges of set entries, in increasing order
+    pub fn runs(&self) -> impl ExactSizeIterator<Item = Range<usize>> + '_ {
+        self.runs.iter().cloned()
+    }
+
+    /// Copies the entries into a dense vector
+    pub fn to_tightvec<W: Word>(&self) -> TightVec<W> {
+        let mut v = TightVec::with_len_and_value(self.len, false);
+
+        for run in &self.runs {
+            v.slice_mut(run.clone()).fill(true);
+        }
+
+        v
+    }
+
+    /// runs overlapping `[start, end)`
+    fn overlapping(&self, start: usize, end: usize) -> impl Iterator<Item = &Range<usize>> {
+        let first = self.runs.partition_point(|run| run.end <= start);
+
+        self.runs[first..]
+            .iter()
+            .take_while(move |run| run.start < end)
+    }
+
+    fn fill_resolved(&mut self, start: usize, end: usize, value: bool) {
+        if start == end {
+            return;
+        }
+
+        // the runs overlapping or touching `[start, end)`
+        let first = self.runs.partition_point(|run| run.end < start);
+        let last = self.runs.partition_point(|run| run.start <= end);
+
+        if value {
+            let (merged_start, merged_end) = if first < last {
+                (
+                    self.runs[first].start.min(start),
+                    self.runs[last - 1].end.max(end),
+                )
+            } else {
+                (start, end)
+            };
+
+            self.runs
+                .splice(first..last, iter::once(merged_start..merged_end));
+        } else if first < last {
+            let before = self.runs[first].start..start;
+            let after = end..self.runs[last - 1].end;
+
+            self.runs.splice(
+                first..last,
+                [before, after].into_iter().filter(|run| !run.is_empty()),
+            );
+        }
+    }
+}
+
+/// Compresses the entries of a view, scanning for run boundaries a word at a time
+impl<W: Word> From<BitSlice<'_, W>> for RunVec {
+    fn from(bits: BitSlice<'_, W>) -> Self {
+        Self {
+            runs: bits
+                .runs()
+                .filter(|&(_, _, value)| value)
+                .map(|(start, end, _)| start..end + 1)
+                .collect(),
+            len: bits.len(),
+        }
+    }
+}
+
+impl<W: Word> From<&TightVec<W>> for RunVec {
+    fn from(v: &TightVec<W>) -> Self {
+        v.as_bitslice().into()
+    }
+}
+
+impl FromIterator<bool> for RunVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut v = RunVec::default();
+
+        for value in iter {
+            v.push(value);
+        }
+
+        v
+    }
+}
+
+impl BitContainer for RunVec {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        RunVec::get(self, index)
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        RunVec::count_ones_in(self, range)
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.runs.iter().flat_map(Range::clone)
+    }
+}
+
+impl BitContainerMut for RunVec {
+    #[track_caller]
+    fn set(&mut self, index: usize, value: bool) {
+        RunVec::set(self, index, value)
+    }
+
+    #[track_caller]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        RunVec::fill_range(self, range, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::run_pattern;
+    use crate::{BitContainerMut, RunVec, TightVec, TightVecError};
+
+    /// applies the same edits to both containers and checks that every query agrees
+    fn same_behaviour(dense: &mut impl BitContainerMut, sparse: &mut impl BitContainerMut) {
+        let len = dense.len();
+        assert_eq!(sparse.len(), len);
+
+        for (step, (start, end)) in [(3, 40), (0, 1), (90, 150), (37, 38), (100, 101), (60, 130)]
+            .into_iter()
+            .enumerate()
+        {
+            let (start, end) = (start.min(len), end.min(len));
+            dense.fill_range(start..end, step % 2 == 0);
+            sparse.fill_range(start..end, step % 2 == 0);
+
+            if start < len {
+                dense.set(start / 2, step % 3 == 0);
+                sparse.set(start / 2, step % 3 == 0);
+            }
+
+            assert!((0..len).all(|i| dense.get(i) == sparse.get(i)));
+            assert!(dense.iter_ones().eq(sparse.iter_ones()));
+
+            for (start, end) in [(0, len), (start, end), (end / 3, end)] {
+                assert_eq!(
+                    dense.count_ones_in(start..end),
+                    sparse.count_ones_in(start..end)
+                );
+                assert_eq!(dense.all_in(start..end), sparse.all_in(start..end));
+                assert_eq!(dense.any_in(start..end), sparse.any_in(start..end));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_tightvec() {
+        for len in [0, 1, 64, 150, 200] {
+            let mut dense: TightVec = run_pattern(len);
+            let mut sparse = RunVec::from(&dense);
+
+            same_behaviour(&mut dense, &mut sparse);
+
+            assert_eq!(sparse.to_tightvec::<u64>(), dense);
+            assert_eq!(RunVec::from(&dense), sparse);
+        }
+    }
+
+    #[test]
+    fn keeps_runs_canonical() {
+        let mut v = RunVec::new(100);
+
+        v.fill_range(10..20, true);
+        v.fill_range(30..40, true);
+        assert_eq!(v.runs().collect::<Vec<_>>(), [10..20, 30..40]);
+
+        v.fill_range(20..30, true);
+        assert_eq!(v.runs().next(), Some(10..40));
+        assert_eq!(v.run_count(), 1);
+
+        v.fill_range(15..25, false);
+        v.set(12, false);
+        assert_eq!(v.runs().collect::<Vec<_>>(), [10..12, 13..15, 25..40]);
+
+        v.set(12, true);
+        v.fill_range(14..26, true);
+        assert_eq!(
+            v,
+            RunVec::from_iter((0..100).map(|i| (10..40).contains(&i)))
+        );
+        assert_eq!(v.run_count(), 1);
+    }
+
+    #[test]
+    fn huge_lengths_stay_small() {
+        let mut v = RunVec::new(usize::MAX);
+
+        v.fill_range(5..usize::MAX / 2, true);
+        v.set(usize::MAX / 4, false);
+
+        assert_eq!(v.count_ones_in(..), usize::MAX / 2 - 6);
+        assert!(v.get(usize::MAX / 8));
+        assert!(!v.get(usize::MAX / 4));
+        assert_eq!(v.run_count(), 2);
+    }
+
+    #[test]
+    fn checked_access() {
+        let mut v = RunVec::new(10);
+
+        assert_eq!(
+            v.try_get(10),
+            Err(TightVecError::IndexOutOfBounds { index: 10, len: 10 })
+        );
+        assert_eq!(
+            v.try_fill_range(5..11, true),
+            Err(TightVecError::RangeOutOfBounds { end: 11, len: 10 })
+        );
+        assert_eq!(v.try_count_ones_in(..), Ok(0));
+    }
+}
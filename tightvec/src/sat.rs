@@ -0,0 +1,269 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+use crate::error::or_panic;
+use crate::grid::{BitRows, try_resolve_rect};
+use crate::{TightVecError, Word};
+
+/// Summed-area table over a bit grid: counts the set cells of any rectangle in O(1).
+///
+/// Stores `(rows + 1) * (cols + 1)` prefix counts. Each counter is only as wide as needed to hold
+/// `rows * cols`, so small grids get `u8` or `u16` counters. The table is a snapshot; it doesn't
+/// follow later changes to the grid it was built from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SummedAreaTable {
+    rows: usize,
+    cols: usize,
+    counts: Counts,
+}
+
+/// prefix counts, `counts[row * (cols + 1) + col]` being the number of set cells in `[0, row) x [0, col)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Counts {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+}
+
+impl SummedAreaTable {
+    /// Builds the table for `grid`. Panics if the number of prefix counts overflows `usize`.
+    #[track_caller]
+    pub fn new<R: BitRows + ?Sized>(grid: &R) -> Self {
+        let (rows, cols) = (grid.rows(), grid.cols());
+        let max_count = (rows as u64).saturating_mul(cols as u64);
+
+        let counts = if max_count <= u8::MAX as u64 {
+            Counts::U8(build(grid))
+        } else if max_count <= u16::MAX as u64 {
+            Counts::U16(build(grid))
+        } else if max_count <= u32::MAX as u64 {
+            Counts::U32(build(grid))
+        } else {
+            Counts::U64(build(grid))
+        };
+
+        Self { rows, cols, counts }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Width of one stored counter in bits
+    pub fn counter_bits(&self) -> usize {
+        match &self.counts {
+            Counts::U8(_) => 8,
+            Counts::U16(_) => 16,
+            Counts::U32(_) => 32,
+            Counts::U64(_) => 64,
+        }
+    }
+
+    /// Number of set cells in the rectangle. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn count(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> usize {
+        or_panic(self.try_count(rows, cols))
+    }
+
+    pub fn try_count(
+        &self,
+        rows: impl RangeBounds<usize>,
+        cols: impl RangeBounds<usize>,
+    ) -> Result<usize, TightVecError> {
+        let (rows, cols) = try_resolve_rect(rows, cols, self.rows, self.cols)?;
+
+        let total = self.prefix(rows.end, cols.end) + self.prefix(rows.start, cols.start)
+            - self.prefix(rows.start, cols.end)
+            - self.prefix(rows.end, cols.start);
+
+        Ok(total as usize)
+    }
+
+    /// Whether every cell of the rectangle is set. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn all_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> bool {
+        let (rows, cols) = or_panic(try_resolve_rect(rows, cols, self.rows, self.cols));
+        let area = rows.len() * cols.len();
+
+        self.count(rows, cols) == area
+    }
+
+    /// Whether any cell of the rectangle is set. Panics if the rectangle is out of bounds.
+    #[track_caller]
+    pub fn any_set(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> bool {
+        self.count(rows, cols) > 0
+    }
+
+    /// number of set cells in `[0, row) x [0, col)`
+    fn prefix(&self, row: usize, col: usize) -> u64 {
+        let index = row * (self.cols + 1) + col;
+
+        match &self.counts {
+            Counts::U8(counts) => counts[index] as u64,
+            Counts::U16(counts) => counts[index] as u64,
+            Counts::U32(counts) => counts[index] as u64,
+            Counts::U64(counts) => counts[index],
+        }
+    }
+}
+
+/// an integer type the prefix counts can be stored in
+trait Counter: Copy {
+    const ZERO: Self;
+
+    /// truncating conversion, callers make sure the value fits
+    fn from_u64(value: u64) -> Self;
+
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! impl_counter {
+    ($($t:ty),*) => {$(
+        impl Counter for $t {
+            const ZERO: Self = 0;
+
+            fn from_u64(value: u64) -> Self {
+                value as $t
+            }
+
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+        }
+    )*};
+}
+
+impl_counter!(u8, u16, u32, u64);
+
+#[track_caller]
+fn build<R: BitRows + ?Sized, C: Counter>(grid: &R) -> Vec<C> {
+    let (rows, cols) = (grid.rows(), grid.cols());
+    let size = cols
+        .checked_add(1)
+        .and_then(|stride| rows.checked_add(1)?.checked_mul(stride))
+        .expect("summed-area table size overflows usize");
+    let stride = cols + 1;
+
+    let mut counts = vec![C::ZERO; size];
+
+    for row in 0..rows {
+        let bits = grid.row(row);
+        let mut row_count = 0;
+        let mut col = 0;
+
+        for chunk_index in 0..bits.chunk_count() {
+            let (chunk, chunk_bits) = bits.chunk(chunk_index);
+
+            for bit in 0..chunk_bits {
+                row_count +=
+                    ((chunk >> bit) & <R::Word as Word>::ONE != <R::Word as Word>::ZERO) as u64;
+                col += 1;
+
+                let above = counts[row * stride + col].to_u64();
+                counts[(row + 1) * stride + col] = C::from_u64(above + row_count);
+            }
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::grid_pattern;
+    use crate::{BitGrid, SummedAreaTable, TightVec, TightVecError};
+
+    fn brute_force(
+        grid: &BitGrid,
+        rows: core::ops::Range<usize>,
+        cols: core::ops::Range<usize>,
+    ) -> usize {
+        rows.flat_map(|row| cols.clone().map(move |col| (row, col)))
+            .filter(|&(row, col)| grid.get(row, col))
+            .count()
+    }
+
+    #[test]
+    fn counts_match_brute_force() {
+        let grid: BitGrid = grid_pattern(13, 150);
+        let table = SummedAreaTable::new(&grid);
+
+        for (rows, cols) in [
+            (0..13, 0..150),
+            (2..9, 60..140),
+            (5..6, 63..65),
+            (3..3, 0..150),
+            (0..13, 149..150),
+        ] {
+            assert_eq!(
+                table.count(rows.clone(), cols.clone()),
+                brute_force(&grid, rows, cols)
+            );
+        }
+    }
+
+    #[test]
+    fn rectangle_predicates() {
+        let mut grid: BitGrid = BitGrid::new(20, 100);
+        grid.fill_rect(3..=10, 20..=80, true);
+
+        let table = SummedAreaTable::new(&grid);
+
+        assert!(table.all_set(3..=10, 20..=80));
+        assert!(!table.all_set(3..=11, 20..=80));
+        assert!(table.any_set(.., 80..));
+        assert!(!table.any_set(11.., ..));
+        assert!(table.all_set(5..5, ..));
+        assert_eq!(
+            table.try_count(0..21, ..),
+            Err(TightVecError::RangeOutOfBounds { end: 21, len: 20 })
+        );
+    }
+
+    #[test]
+    fn counter_width_follows_dimensions() {
+        assert_eq!(
+            SummedAreaTable::new(&BitGrid::<u64>::new(10, 25)).counter_bits(),
+            8
+        );
+        assert_eq!(
+            SummedAreaTable::new(&BitGrid::<u64>::new(10, 26)).counter_bits(),
+            16
+        );
+        assert_eq!(
+            SummedAreaTable::new(&BitGrid::<u64>::new(300, 300)).counter_bits(),
+            32
+        );
+    }
+
+    #[test]
+    fn from_tightvec_rows() {
+        let rows: Vec<TightVec> = vec!["0110".parse().unwrap(), "1111".parse().unwrap()];
+
+        let table = SummedAreaTable::new(&rows);
+
+        assert_eq!(table.count(.., ..), 6);
+        assert_eq!(table.count(.., 1..3), 4);
+        assert_eq!(table.count(1.., ..1), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "row 1 has length 5, expected 4")]
+    fn rejects_ragged_rows() {
+        let rows: Vec<TightVec> = vec!["0110".parse().unwrap(), "11111".parse().unwrap()];
+
+        SummedAreaTable::new(&rows);
+    }
+
+    #[test]
+    #[should_panic(expected = "summed-area table size overflows usize")]
+    fn oversized_table() {
+        SummedAreaTable::new(&BitGrid::<u8>::new(usize::MAX, 0));
+    }
+}
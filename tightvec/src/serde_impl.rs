@@ -0,0 +1,209 @@
+//! Serializes a [`TightVec`] as a struct of its length and packed storage words
+//! (`{ len, words }`). Bits past `len` in the last word are written as zero.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Formatter};
+use core::marker::PhantomData;
+
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{TightVec, Word};
+
+const FIELDS: &[&str] = &["len", "words"];
+
+impl<W: Word + Serialize> Serialize for TightVec<W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TightVec", 2)?;
+        state.serialize_field("len", &self.len)?;
+        state.serialize_field("words", &Words(self))?;
+        state.end()
+    }
+}
+
+/// the storage words of a vector as a sequence, without copying them
+struct Words<'a, W: Word>(&'a TightVec<W>);
+
+impl<W: Word + Serialize> Serialize for Words<'_, W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.logical_words())
+    }
+}
+
+impl<'de, W: Word + Deserialize<'de>> Deserialize<'de> for TightVec<W> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("TightVec", FIELDS, TightVecVisitor(PhantomData))
+    }
+}
+
+enum Field {
+    Len,
+    Words,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("`len` or `words`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                match value {
+                    "len" => Ok(Field::Len),
+                    "words" => Ok(Field::Words),
+                    _ => Err(E::unknown_field(value, FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct TightVecVisitor<W>(PhantomData<W>);
+
+impl<W: Word> TightVecVisitor<W> {
+    fn build<E: de::Error>(len: usize, words: Vec<W>) -> Result<TightVec<W>, E> {
+        if words.len() != len.div_ceil(W::BITS) {
+            return Err(E::invalid_length(
+                words.len(),
+                &"one storage word per started block of `len` bits",
+            ));
+        }
+
+        let mut v = TightVec { inner: words, len };
+        v.clear_unused_bits();
+
+        Ok(v)
+    }
+}
+
+impl<'de, W: Word + Deserialize<'de>> Visitor<'de> for TightVecVisitor<W> {
+    type Value = TightVec<W>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("struct TightVec")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let len = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let words = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        Self::build(len, words)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut len = None;
+        let mut words = None;
+
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Len if len.is_some() => return Err(de::Error::duplicate_field("len")),
+                Field::Words if words.is_some() => {
+                    return Err(de::Error::duplicate_field("words"));
+                }
+                Field::Len => len = Some(map.next_value()?),
+                Field::Words => words = Some(map.next_value()?),
+            }
+        }
+
+        Self::build(
+            len.ok_or_else(|| de::Error::missing_field("len"))?,
+            words.ok_or_else(|| de::Error::missing_field("words"))?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_test::{
+        Token, assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, assert_tokens,
+    };
+
+    use crate::TightVec;
+
+    #[test]
+    fn tokens() {
+        let mut v: TightVec<u8> = "1000000011".parse().unwrap();
+        let tokens = [
+            Token::Struct {
+                name: "TightVec",
+                len: 2,
+            },
+            Token::Str("len"),
+            Token::U64(10),
+            Token::Str("words"),
+            Token::Seq { len: Some(2) },
+            Token::U8(0x01),
+            Token::U8(0x03),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ];
+
+        assert_tokens(&v, &tokens);
+
+        // bits past the length aren't written
+        v.inner[1] |= 0x80;
+        assert_ser_tokens(&v, &tokens);
+    }
+
+    #[test]
+    fn rejects_wrong_word_count() {
+        assert_de_tokens_error::<TightVec<u8>>(
+            &[
+                Token::Struct {
+                    name: "TightVec",
+                    len: 2,
+                },
+                Token::Str("len"),
+                Token::U64(10),
+                Token::Str("words"),
+                Token::Seq { len: Some(1) },
+                Token::U8(0x01),
+                Token::SeqEnd,
+                Token::StructEnd,
+            ],
+            "invalid length 1, expected one storage word per started block of `len` bits",
+        );
+    }
+
+    #[test]
+    fn from_seq_and_owned_keys() {
+        let v: TightVec<u8> = "101".parse().unwrap();
+
+        assert_de_tokens(
+            &v,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U64(3),
+                Token::Seq { len: Some(1) },
+                Token::U8(0x05),
+                Token::SeqEnd,
+                Token::SeqEnd,
+            ],
+        );
+        assert_de_tokens(
+            &v,
+            &[
+                Token::Map { len: Some(2) },
+                Token::String("words"),
+                Token::Seq { len: Some(1) },
+                Token::U8(0xfd),
+                Token::SeqEnd,
+                Token::String("len"),
+                Token::U64(3),
+                Token::MapEnd,
+            ],
+        );
+    }
+}
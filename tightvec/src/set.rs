@@ -0,0 +1,287 @@
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter;
+
+use crate::{BitContainer, StorageItem, TightVec, Word};
+
+/// A set of non-negative integers, stored as one bit per integer up to the largest one inserted.
+///
+/// Grows on [`insert`](Self::insert) and never shrinks on its own. The number of elements is
+/// cached, so [`len`](Self::len) doesn't count bits.
+#[derive(Clone, Default)]
+pub struct BitSet<W: Word = StorageItem> {
+    bits: TightVec<W>,
+    len: usize,
+}
+
+impl<W: Word> BitSet<W> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty set that holds the integers below `capacity` without growing
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bits: TightVec::with_len_and_value(capacity, false),
+            len: 0,
+        }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The integers below this fit without growing
+    pub fn capacity(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.bits.len() && self.bits.index(value)
+    }
+
+    /// Adds `value`, growing the set if needed. Returns whether it was newly inserted.
+    pub fn insert(&mut self, value: usize) -> bool {
+        if value >= self.bits.len() {
+            self.bits.resize(value + 1, false);
+        } else if self.bits.index(value) {
+            return false;
+        }
+
+        self.bits.set(value, true);
+        self.len += 1;
+
+        true
+    }
+
+    /// Removes `value`. Returns whether it was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+
+        self.bits.set(value, false);
+        self.len -= 1;
+
+        true
+    }
+
+    /// Removes every element, keeping the capacity
+    pub fn clear(&mut self) {
+        self.bits.as_bitslice_mut().fill(false);
+        self.len = 0;
+    }
+
+    /// The elements in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter_ones()
+    }
+
+    /// Elements in either set
+    pub fn union(&self, other: &BitSet<W>) -> BitSet<W> {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Elements in both sets
+    pub fn intersection(&self, other: &BitSet<W>) -> BitSet<W> {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Elements in this set but not in `other`
+    pub fn difference(&self, other: &BitSet<W>) -> BitSet<W> {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Elements in exactly one of the sets
+    pub fn symmetric_difference(&self, other: &BitSet<W>) -> BitSet<W> {
+        self.combine(other, |a, b| a ^ b)
+    }
+
+    /// One bit per integer below the capacity, set for the elements
+    pub fn as_tightvec(&self) -> &TightVec<W> {
+        &self.bits
+    }
+
+    pub fn into_tightvec(self) -> TightVec<W> {
+        self.bits
+    }
+
+    /// applies a word-wise operation to a copy of this set's words and the words of `other`,
+    /// reading the words past the end of the shorter one as zero. `op` must map zeros to zero, so
+    /// the bits past the capacity stay clear.
+    fn combine(&self, other: &BitSet<W>, op: fn(W, W) -> W) -> BitSet<W> {
+        let mut bits = self.bits.clone();
+        bits.resize(self.capacity().max(other.capacity()), false);
+
+        let other_words = other
+            .bits
+            .inner
+            .iter()
+            .copied()
+            .chain(iter::repeat(W::ZERO));
+        for (word, other_word) in bits.inner.iter_mut().zip(other_words) {
+            *word = op(*word, other_word);
+        }
+
+        bits.into()
+    }
+
+    /// the storage words up to the last one holding an element, the same for equal sets
+    fn used_words(&self) -> &[W] {
+        let words = self.bits.get_raw();
+        let used = words
+            .iter()
+            .rposition(|&word| word != W::ZERO)
+            .map_or(0, |last| last + 1);
+
+        &words[..used]
+    }
+}
+
+/// Treats the set entries as the elements
+impl<W: Word> From<TightVec<W>> for BitSet<W> {
+    fn from(bits: TightVec<W>) -> Self {
+        Self {
+            len: bits.as_bitslice().count_ones(),
+            bits,
+        }
+    }
+}
+
+impl<W: Word> FromIterator<usize> for BitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<W: Word> Extend<usize> for BitSet<W> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+/// Sets are equal if they have the same elements, whatever their capacity
+impl<W: Word> PartialEq for BitSet<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.used_words() == other.used_words()
+    }
+}
+
+impl<W: Word> Eq for BitSet<W> {}
+
+/// Like equality, ignores the capacity
+impl<W: Word> Hash for BitSet<W> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.used_words().hash(state);
+    }
+}
+
+impl<W: Word> Debug for BitSet<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+    use std::hash::{BuildHasher, RandomState};
+
+    use crate::{BitSet, TightVec};
+
+    fn sets() -> (BitSet<u8>, BTreeSet<usize>, BitSet<u8>, BTreeSet<usize>) {
+        let a = [0, 3, 4, 9, 17, 64, 65, 130];
+        let b = [1, 3, 9, 10, 65, 200];
+
+        (
+            a.into_iter().collect(),
+            a.into_iter().collect(),
+            b.into_iter().collect(),
+            b.into_iter().collect(),
+        )
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set: BitSet = BitSet::new();
+
+        assert!(set.insert(70));
+        assert!(!set.insert(70));
+        assert!(set.insert(3));
+        assert_eq!(set.capacity(), 71);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(3) && set.contains(70));
+        assert!(!set.contains(4) && !set.contains(1000));
+
+        assert!(set.remove(70));
+        assert!(!set.remove(70));
+        assert!(!set.remove(1000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [3]);
+
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.capacity(), 71);
+    }
+
+    #[test]
+    fn set_operations() {
+        let (a, a_ref, b, b_ref) = sets();
+
+        for (result, expected) in [
+            (
+                a.union(&b),
+                a_ref.union(&b_ref).copied().collect::<Vec<_>>(),
+            ),
+            (
+                a.intersection(&b),
+                a_ref.intersection(&b_ref).copied().collect(),
+            ),
+            (
+                a.difference(&b),
+                a_ref.difference(&b_ref).copied().collect(),
+            ),
+            (
+                b.difference(&a),
+                b_ref.difference(&a_ref).copied().collect(),
+            ),
+            (
+                a.symmetric_difference(&b),
+                a_ref.symmetric_difference(&b_ref).copied().collect(),
+            ),
+        ] {
+            assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+            assert_eq!(result.len(), expected.len());
+        }
+    }
+
+    #[test]
+    fn equality_ignores_capacity() {
+        let mut a: BitSet = BitSet::with_capacity(500);
+        a.insert(7);
+        let b: BitSet = BitSet::from_iter([7]);
+
+        assert_eq!(a, b);
+        assert_ne!(a, BitSet::new());
+
+        let hasher = RandomState::new();
+        assert_eq!(hasher.hash_one(&a), hasher.hash_one(&b));
+        assert_eq!(
+            hasher.hash_one(BitSet::<u8>::with_capacity(100)),
+            hasher.hash_one(BitSet::<u8>::new())
+        );
+        assert_eq!(format!("{a:?}"), "{7}");
+
+        let from_bits: BitSet = "0110_0001".parse::<TightVec>().unwrap().into();
+        assert_eq!(from_bits.len(), 3);
+        assert_eq!(from_bits, BitSet::from_iter([1, 2, 7]));
+    }
+}
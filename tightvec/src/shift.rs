@@ -0,0 +1,204 @@
+use core::ops::{ShlAssign, ShrAssign};
+
+use crate::{TightVec, Word};
+
+/// Shifts and rotations treat index 0 as the least significant bit, like the integer operators do:
+/// shifting or rotating left moves entries towards higher indices. Both work a word at a time.
+impl<W: Word> TightVec<W> {
+    /// Moves every entry `n` indices up. Entries shifted past the end are dropped, the lowest `n`
+    /// entries become `false`. The length stays the same.
+    pub fn shl(&mut self, n: usize) {
+        if n >= self.len {
+            self.inner.fill(W::ZERO);
+            return;
+        }
+
+        let word_shift = n / W::BITS;
+        let bit_shift = n % W::BITS;
+
+        for word_index in (word_shift..self.inner.len()).rev() {
+            let source = word_index - word_shift;
+            let mut word = self.inner[source] << bit_shift;
+
+            if bit_shift != 0 && source > 0 {
+                word |= self.inner[source - 1] >> (W::BITS - bit_shift);
+            }
+
+            self.inner[word_index] = word;
+        }
+
+        self.inner[..word_shift].fill(W::ZERO);
+        self.clear_unused_bits();
+    }
+
+    /// Moves every entry `n` indices down. Entries shifted below index 0 are dropped, the highest
+    /// `n` entries become `false`. The length stays the same.
+    pub fn shr(&mut self, n: usize) {
+        if n >= self.len {
+            self.inner.fill(W::ZERO);
+            return;
+        }
+
+        self.clear_unused_bits();
+
+        let word_shift = n / W::BITS;
+        let bit_shift = n % W::BITS;
+        let word_count = self.inner.len();
+
+        for word_index in 0..word_count - word_shift {
+            let source = word_index + word_shift;
+            let mut word = self.inner[source] >> bit_shift;
+
+            if bit_shift != 0 && source + 1 < word_count {
+                word |= self.inner[source + 1] << (W::BITS - bit_shift);
+            }
+
+            self.inner[word_index] = word;
+        }
+
+        self.inner[word_count - word_shift..].fill(W::ZERO);
+    }
+
+    /// Moves every entry `n` indices up, wrapping the entries shifted past the end around to the
+    /// start. Note that this is the direction of [`shl`](Self::shl), the opposite of
+    /// [`slice::rotate_left`].
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        let n = n % self.len;
+
+        // only the entries that wrap around are copied, so wrap the shorter way
+        if n > self.len / 2 {
+            return self.rotate_right(self.len - n);
+        }
+
+        let wrapped = self.slice(self.len - n..).to_tightvec();
+        self.shl(n);
+        self.slice_mut(..n)
+            .copy_from_bitslice(&wrapped.as_bitslice());
+    }
+
+    /// Moves every entry `n` indices down, wrapping the entries shifted below index 0 around to the
+    /// end. Note that this is the direction of [`shr`](Self::shr), the opposite of
+    /// [`slice::rotate_right`].
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        let n = n % self.len;
+
+        if n > self.len / 2 {
+            return self.rotate_left(self.len - n);
+        }
+
+        let wrapped = self.slice(..n).to_tightvec();
+        self.shr(n);
+        let len = self.len;
+        self.slice_mut(len - n..)
+            .copy_from_bitslice(&wrapped.as_bitslice());
+    }
+}
+
+impl<W: Word> ShlAssign<usize> for TightVec<W> {
+    fn shl_assign(&mut self, n: usize) {
+        self.shl(n);
+    }
+}
+
+impl<W: Word> ShrAssign<usize> for TightVec<W> {
+    fn shr_assign(&mut self, n: usize) {
+        self.shr(n);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::{from_bits, pattern_bits};
+    use crate::{TightVec, Word};
+
+    fn pattern<W: Word>(len: usize) -> (TightVec<W>, Vec<bool>) {
+        let bits = pattern_bits(len, 7);
+
+        (from_bits(&bits), bits)
+    }
+
+    fn check_shifts<W: Word>() {
+        for len in [1, 63, 64, 65, 200] {
+            for n in [0, 1, 7, 63, 64, 65, 130, 199, 200, 500] {
+                let (mut v, bits) = pattern::<W>(len);
+                v <<= n;
+                let expected: Vec<bool> = (0..len).map(|i| i >= n && bits[i - n]).collect();
+                assert!(v.iter().eq(expected.iter().copied()), "shl {len} by {n}");
+
+                let (mut v, bits) = pattern::<W>(len);
+                v >>= n;
+                let expected: Vec<bool> = (0..len).map(|i| i + n < len && bits[i + n]).collect();
+                assert!(v.iter().eq(expected.iter().copied()), "shr {len} by {n}");
+
+                let (mut v, mut bits) = pattern::<W>(len);
+                v.rotate_left(n);
+                bits.rotate_right(n % len);
+                assert!(
+                    v.iter().eq(bits.iter().copied()),
+                    "rotate_left {len} by {n}"
+                );
+
+                let (mut v, mut bits) = pattern::<W>(len);
+                v.rotate_right(n);
+                bits.rotate_left(n % len);
+                assert!(
+                    v.iter().eq(bits.iter().copied()),
+                    "rotate_right {len} by {n}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shifts_and_rotations() {
+        check_shifts::<u8>();
+        check_shifts::<u64>();
+        check_shifts::<u128>();
+    }
+
+    #[test]
+    fn shl_drops_high_entries() {
+        let mut v: TightVec = TightVec::with_len_and_value(70, true);
+
+        v.shl(10);
+        v.resize(80, false);
+
+        assert_eq!(v.slice(..).count_ones(), 60);
+        assert!(!v.slice(..10).any());
+        assert!(!v.slice(70..).any());
+    }
+
+    #[test]
+    fn rotations_move_like_shifts() {
+        let mut v: TightVec = "1100_0000_01".parse().unwrap();
+        let words = v.get_raw().as_ptr();
+
+        v.rotate_left(2);
+        assert_eq!(v.to_string(), "0111000000");
+        v.rotate_right(3);
+        assert_eq!(v.to_string(), "1000000011");
+        v.rotate_left(9);
+        assert_eq!(v.to_string(), "0000000111");
+        assert_eq!(v.get_raw().as_ptr(), words);
+    }
+
+    #[test]
+    fn empty() {
+        let mut v: TightVec = TightVec::default();
+
+        v.shl(3);
+        v.shr(3);
+        v.rotate_left(3);
+        v.rotate_right(3);
+
+        assert!(v.is_empty());
+    }
+}
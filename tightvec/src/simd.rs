@@ -0,0 +1,240 @@
+//! Bulk reductions over whole storage words: counting set bits and checking whether all or any
+//! are set.
+//!
+//! Every word type is a plain integer without padding (see [`Word`]), so the kernels work on the
+//! raw bytes of the words and are shared by all word widths. On x86_64 the AVX2 versions are
+//! picked at runtime if the CPU supports them. Without the `std` feature there's no runtime
+//! detection, so they're only built for targets that enable AVX2 at compile time. Everywhere else,
+//! and for short inputs, the scalar versions run. Both give identical results.
+//!
+//! Only reductions get hand-written versions: `benches/bulk.rs` shows AVX2 counting well ahead of
+//! the word loop, while element-wise operations such as equality or xor are bound by memory and
+//! the compiler already vectorizes them, so those stay plain word loops.
+
+use core::mem::size_of_val;
+use core::slice;
+
+use crate::Word;
+
+/// inputs shorter than this many bytes aren't worth the feature check
+#[cfg(all(target_arch = "x86_64", any(feature = "std", target_feature = "avx2")))]
+const MIN_SIMD_BYTES: usize = 64;
+
+/// the raw bytes of `words`
+pub(crate) fn bytes<W: Word>(words: &[W]) -> &[u8] {
+    // SAFETY: words are plain integers without padding, so every byte is initialized, and `u8`
+    // has no alignment requirement
+    unsafe { slice::from_raw_parts(words.as_ptr().cast(), size_of_val(words)) }
+}
+
+/// whether the CPU supports AVX2
+#[cfg(all(target_arch = "x86_64", any(feature = "std", target_feature = "avx2")))]
+fn has_avx2() -> bool {
+    #[cfg(feature = "std")]
+    return std::is_x86_feature_detected!("avx2");
+
+    // the target enables AVX2, see above
+    #[cfg(not(feature = "std"))]
+    return true;
+}
+
+macro_rules! dispatch {
+    ($len:expr, $name:ident($($arg:expr),*)) => {{
+        #[cfg(all(target_arch = "x86_64", any(feature = "std", target_feature = "avx2")))]
+        if $len >= MIN_SIMD_BYTES && has_avx2() {
+            // SAFETY: AVX2 support was just checked
+            return unsafe { avx2::$name($($arg),*) };
+        }
+
+        scalar::$name($($arg),*)
+    }};
+}
+
+/// Number of set bits
+pub(crate) fn count_ones(bytes: &[u8]) -> usize {
+    dispatch!(bytes.len(), count_ones(bytes))
+}
+
+/// Whether every bit is set
+pub(crate) fn all_ones(bytes: &[u8]) -> bool {
+    dispatch!(bytes.len(), all_ones(bytes))
+}
+
+/// Whether any bit is set
+pub(crate) fn any_ones(bytes: &[u8]) -> bool {
+    dispatch!(bytes.len(), any_ones(bytes))
+}
+
+mod scalar {
+    const WORD: usize = size_of::<u64>();
+
+    fn word(chunk: &[u8]) -> u64 {
+        u64::from_ne_bytes(chunk.try_into().unwrap())
+    }
+
+    pub(super) fn count_ones(bytes: &[u8]) -> usize {
+        let chunks = bytes.chunks_exact(WORD);
+        let tail = chunks.remainder();
+
+        chunks
+            .map(|chunk| word(chunk).count_ones() as usize)
+            .chain(tail.iter().map(|byte| byte.count_ones() as usize))
+            .sum()
+    }
+
+    pub(super) fn all_ones(bytes: &[u8]) -> bool {
+        let chunks = bytes.chunks_exact(WORD);
+        let tail = chunks.remainder();
+
+        chunks.map(word).all(|word| word == u64::MAX) && tail.iter().all(|&byte| byte == u8::MAX)
+    }
+
+    pub(super) fn any_ones(bytes: &[u8]) -> bool {
+        let chunks = bytes.chunks_exact(WORD);
+        let tail = chunks.remainder();
+
+        chunks.map(word).any(|word| word != 0) || tail.iter().any(|&byte| byte != 0)
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", any(feature = "std", target_feature = "avx2")))]
+mod avx2 {
+    use core::arch::x86_64::*;
+
+    use super::scalar;
+
+    const LANE: usize = size_of::<__m256i>();
+
+    /// # Safety
+    ///
+    /// `chunk` must hold at least 32 bytes
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(chunk: &[u8]) -> __m256i {
+        // SAFETY: the caller guarantees 32 readable bytes, `loadu` has no alignment requirement
+        unsafe { _mm256_loadu_si256(chunk.as_ptr().cast()) }
+    }
+
+    /// Counts through a nibble lookup table (Muła's algorithm), summing the byte counts with `sad`
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn count_ones(bytes: &[u8]) -> usize {
+        let chunks = bytes.chunks_exact(LANE);
+        let tail = chunks.remainder();
+
+        let table = _mm256_setr_epi8(
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, 0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2,
+            3, 3, 4,
+        );
+        let low_nibbles = _mm256_set1_epi8(0x0f);
+        let mut total = _mm256_setzero_si256();
+
+        for chunk in chunks {
+            // SAFETY: exact chunks are 32 bytes long
+            let v = unsafe { load(chunk) };
+
+            let low = _mm256_and_si256(v, low_nibbles);
+            let high = _mm256_and_si256(_mm256_srli_epi16::<4>(v), low_nibbles);
+            let counts = _mm256_add_epi8(
+                _mm256_shuffle_epi8(table, low),
+                _mm256_shuffle_epi8(table, high),
+            );
+
+            total = _mm256_add_epi64(total, _mm256_sad_epu8(counts, _mm256_setzero_si256()));
+        }
+
+        let lanes = [
+            _mm256_extract_epi64::<0>(total),
+            _mm256_extract_epi64::<1>(total),
+            _mm256_extract_epi64::<2>(total),
+            _mm256_extract_epi64::<3>(total),
+        ];
+
+        lanes.iter().sum::<i64>() as usize + scalar::count_ones(tail)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn all_ones(bytes: &[u8]) -> bool {
+        let chunks = bytes.chunks_exact(LANE);
+        let tail = chunks.remainder();
+        let ones = _mm256_set1_epi8(-1);
+
+        for chunk in chunks {
+            // SAFETY: exact chunks are 32 bytes long
+            if _mm256_testc_si256(unsafe { load(chunk) }, ones) == 0 {
+                return false;
+            }
+        }
+
+        scalar::all_ones(tail)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn any_ones(bytes: &[u8]) -> bool {
+        let chunks = bytes.chunks_exact(LANE);
+        let tail = chunks.remainder();
+
+        for chunk in chunks {
+            // SAFETY: exact chunks are 32 bytes long
+            let v = unsafe { load(chunk) };
+
+            if _mm256_testz_si256(v, v) == 0 {
+                return true;
+            }
+        }
+
+        scalar::any_ones(tail)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::scalar;
+    use crate::test_util::random;
+
+    fn data(len: usize, seed: u64) -> Vec<u8> {
+        random(seed)
+            .map(|state| (state >> 56) as u8)
+            .take(len)
+            .collect()
+    }
+
+    #[test]
+    fn dispatch_matches_scalar() {
+        for len in [0, 7, 31, 32, 63, 64, 65, 100, 1000, 4099] {
+            let a = data(len, 1);
+            assert_eq!(super::count_ones(&a), scalar::count_ones(&a));
+
+            let ones = vec![u8::MAX; len];
+            assert!(super::all_ones(&ones));
+            assert!(!super::any_ones(&vec![0; len]));
+
+            if len > 0 {
+                let mut almost = ones.clone();
+                almost[len - 1] = 0xfe;
+                assert!(!super::all_ones(&almost));
+
+                let mut single = vec![0; len];
+                single[len - 1] = 0x10;
+                assert!(super::any_ones(&single));
+            }
+        }
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(feature = "std", target_feature = "avx2")))]
+    #[test]
+    fn avx2_matches_scalar() {
+        if !std::is_x86_feature_detected!("avx2") {
+            return;
+        }
+
+        for len in [0, 32, 33, 96, 1000] {
+            let a = data(len, 3);
+
+            // SAFETY: AVX2 support was just checked
+            unsafe {
+                assert_eq!(super::avx2::count_ones(&a), scalar::count_ones(&a));
+                assert_eq!(super::avx2::any_ones(&a), scalar::any_ones(&a));
+                assert_eq!(super::avx2::all_ones(&a), scalar::all_ones(&a));
+            }
+        }
+    }
+}
@@ -0,0 +1,696 @@
+use core::ops::{Bound, Range, RangeBounds};
+
+use crate::error::or_panic;
+use crate::simd;
+use crate::{StorageItem, TightVec, TightVecError, Word};
+
+/// A borrowed, read-only view of a range of bits, starting at an arbitrary bit offset
+#[derive(Clone, Copy)]
+pub struct BitSlice<'a, W: Word = StorageItem> {
+    words: &'a [W],
+    /// bit offset of the first entry inside `words[0]`, always below `W::BITS`
+    offset: usize,
+    len: usize,
+}
+
+/// A borrowed, mutable view of a range of bits, starting at an arbitrary bit offset
+pub struct BitSliceMut<'a, W: Word = StorageItem> {
+    words: &'a mut [W],
+    /// bit offset of the first entry inside `words[0]`, always below `W::BITS`
+    offset: usize,
+    len: usize,
+}
+
+/// Iterator over the entries of a [`BitSlice`]
+#[derive(Clone, Debug)]
+pub struct Iter<'a, W: Word = StorageItem> {
+    slice: BitSlice<'a, W>,
+    front: usize,
+    back: usize,
+}
+
+/// Iterator over the maximal runs of equal entries of a [`BitSlice`], as
+/// `(start, end_inclusive, value)`
+#[derive(Clone, Debug)]
+pub struct Runs<'a, W: Word = StorageItem> {
+    slice: BitSlice<'a, W>,
+    next: usize,
+}
+
+impl<'a, W: Word> BitSlice<'a, W> {
+    /// View of `len` bits of `words`, starting at bit `start`
+    pub(crate) fn new(words: &'a [W], start: usize, len: usize) -> Self {
+        let first_word = start / W::BITS;
+        let offset = start % W::BITS;
+        let word_count = (offset + len).div_ceil(W::BITS);
+
+        Self {
+            words: &words[first_word..first_word + word_count],
+            offset,
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn index(&self, index: usize) -> bool {
+        or_panic(self.try_index(index))
+    }
+
+    pub fn try_index(&self, index: usize) -> Result<bool, TightVecError> {
+        check_index(index, self.len)?;
+
+        let bit = self.offset + index;
+
+        Ok(self.words[bit / W::BITS] & (W::ONE << (bit % W::BITS)) != W::ZERO)
+    }
+
+    /// Sub-view of this view. The range is relative to the start of this view.
+    /// Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> BitSlice<'a, W> {
+        or_panic(self.try_slice(range))
+    }
+
+    pub fn try_slice(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<BitSlice<'a, W>, TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        Ok(BitSlice::new(self.words, self.offset + start, end - start))
+    }
+
+    /// Number of set entries
+    pub fn count_ones(&self) -> usize {
+        simd::count_ones(simd::bytes(self.full_words()))
+            + self
+                .edge_words()
+                .map(|(word, mask)| (word & mask).count_ones() as usize)
+                .sum::<usize>()
+    }
+
+    /// Number of unset entries
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Whether every entry is set. True for an empty view.
+    pub fn all(&self) -> bool {
+        self.edge_words().all(|(word, mask)| word & mask == mask)
+            && simd::all_ones(simd::bytes(self.full_words()))
+    }
+
+    /// Whether at least one entry is set
+    pub fn any(&self) -> bool {
+        self.edge_words().any(|(word, mask)| word & mask != W::ZERO)
+            || simd::any_ones(simd::bytes(self.full_words()))
+    }
+
+    pub fn iter(&self) -> Iter<'a, W> {
+        Iter {
+            slice: *self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    /// Iterates over the maximal runs of equal entries, finding each run's end a word at a time
+    pub fn runs(&self) -> Runs<'a, W> {
+        Runs {
+            slice: *self,
+            next: 0,
+        }
+    }
+
+    /// Copies the viewed bits into a new, owned vector
+    pub fn to_tightvec(&self) -> TightVec<W> {
+        let mut v = TightVec::with_capacity(self.len);
+
+        for chunk_index in 0..self.chunk_count() {
+            let (chunk, bits) = self.chunk(chunk_index);
+            v.push_bits(chunk, bits);
+        }
+
+        v
+    }
+
+    /// number of `W::BITS` wide chunks needed to cover the view
+    pub(crate) fn chunk_count(&self) -> usize {
+        self.len.div_ceil(W::BITS)
+    }
+
+    /// the entries `[chunk_index * W::BITS, ...)` shifted down to bit 0, along with how many of them are valid
+    pub(crate) fn chunk(&self, chunk_index: usize) -> (W, usize) {
+        let bits = (self.len - chunk_index * W::BITS).min(W::BITS);
+
+        let mut value = self.words[chunk_index] >> self.offset;
+
+        if self.offset != 0
+            && let Some(next) = self.words.get(chunk_index + 1)
+        {
+            value |= *next << (W::BITS - self.offset);
+        }
+
+        (value & W::low_mask(bits), bits)
+    }
+
+    /// first index at or after `from` whose entry is `value`, searching a word at a time
+    pub(crate) fn next_index_of(&self, value: bool, from: usize) -> Option<usize> {
+        let invert = W::fill_value(!value);
+        let first_chunk = from / W::BITS;
+
+        (first_chunk..self.chunk_count()).find_map(|chunk_index| {
+            let (chunk, bits) = self.chunk(chunk_index);
+            let mut matches = (chunk ^ invert) & W::low_mask(bits);
+
+            if chunk_index == first_chunk {
+                matches &= !W::low_mask(from % W::BITS);
+            }
+
+            (matches != W::ZERO).then(|| chunk_index * W::BITS + matches.trailing_zeros() as usize)
+        })
+    }
+
+    /// last index before `before` whose entry is `value`, searching a word at a time
+    pub(crate) fn prev_index_of(&self, value: bool, before: usize) -> Option<usize> {
+        let invert = W::fill_value(!value);
+        let before = before.min(self.len);
+
+        (0..before.div_ceil(W::BITS)).rev().find_map(|chunk_index| {
+            let (chunk, _) = self.chunk(chunk_index);
+            let bits = (before - chunk_index * W::BITS).min(W::BITS);
+            let matches = (chunk ^ invert) & W::low_mask(bits);
+
+            (matches != W::ZERO)
+                .then(|| chunk_index * W::BITS + W::BITS - 1 - matches.leading_zeros() as usize)
+        })
+    }
+
+    /// the storage words entirely covered by the view
+    fn full_words(&self) -> &'a [W] {
+        &self.words[full_word_range::<W>(self.offset, self.len)]
+    }
+
+    /// the at most two storage words only partially covered by the view, along with the mask of
+    /// bits belonging to the view
+    fn edge_words(&self) -> impl Iterator<Item = (W, W)> + 'a {
+        let (words, offset, len) = (self.words, self.offset, self.len);
+
+        edge_word_indices::<W>(offset, len, words.len())
+            .map(move |word_index| (words[word_index], word_mask(word_index, offset, len)))
+    }
+}
+
+impl<'a, W: Word> BitSliceMut<'a, W> {
+    /// Mutable view of `len` bits of `words`, starting at bit `start`
+    pub(crate) fn new(words: &'a mut [W], start: usize, len: usize) -> Self {
+        let first_word = start / W::BITS;
+        let offset = start % W::BITS;
+        let word_count = (offset + len).div_ceil(W::BITS);
+
+        Self {
+            words: &mut words[first_word..first_word + word_count],
+            offset,
+            len,
+        }
+    }
+
+    /// Read-only view of the same bits
+    pub fn as_bitslice(&self) -> BitSlice<'_, W> {
+        BitSlice {
+            words: self.words,
+            offset: self.offset,
+            len: self.len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn index(&self, index: usize) -> bool {
+        self.as_bitslice().index(index)
+    }
+
+    pub fn try_index(&self, index: usize) -> Result<bool, TightVecError> {
+        self.as_bitslice().try_index(index)
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: bool) {
+        or_panic(self.try_set(index, value))
+    }
+
+    pub fn try_set(&mut self, index: usize, value: bool) -> Result<(), TightVecError> {
+        check_index(index, self.len)?;
+
+        let bit = self.offset + index;
+        let mask = W::ONE << (bit % W::BITS);
+
+        if value {
+            self.words[bit / W::BITS] |= mask;
+        } else {
+            self.words[bit / W::BITS] &= !mask;
+        }
+
+        Ok(())
+    }
+
+    /// Mutable sub-view of this view. The range is relative to the start of this view.
+    /// Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn slice_mut(&mut self, range: impl RangeBounds<usize>) -> BitSliceMut<'_, W> {
+        or_panic(self.try_slice_mut(range))
+    }
+
+    pub fn try_slice_mut(
+        &mut self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<BitSliceMut<'_, W>, TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len)?;
+
+        Ok(BitSliceMut::new(
+            self.words,
+            self.offset + start,
+            end - start,
+        ))
+    }
+
+    /// Sets every entry of the view to `value`
+    pub fn fill(&mut self, value: bool) {
+        let (offset, len) = (self.offset, self.len);
+
+        self.words[full_word_range::<W>(offset, len)].fill(W::fill_value(value));
+
+        for word_index in edge_word_indices::<W>(offset, len, self.words.len()) {
+            let mask = word_mask(word_index, offset, len);
+
+            if value {
+                self.words[word_index] |= mask;
+            } else {
+                self.words[word_index] &= !mask;
+            }
+        }
+    }
+
+    /// Overwrites this view with the entries of `source`. Both must have the same length.
+    pub fn copy_from_bitslice(&mut self, source: &BitSlice<W>) {
+        assert_eq!(
+            self.len, source.len,
+            "source and destination slices must have the same length"
+        );
+
+        for chunk_index in 0..source.chunk_count() {
+            let (chunk, bits) = source.chunk(chunk_index);
+            self.store_bits(chunk_index * W::BITS, chunk, bits);
+        }
+    }
+
+    /// writes the lowest `bits` bits of `value` to the entries starting at `index`
+    pub(crate) fn store_bits(&mut self, index: usize, value: W, bits: usize) {
+        let bit = self.offset + index;
+        let word_index = bit / W::BITS;
+        let shift = bit % W::BITS;
+        let mask = W::low_mask(bits);
+        let value = value & mask;
+
+        let word = &mut self.words[word_index];
+        *word = (*word & !(mask << shift)) | (value << shift);
+
+        if shift + bits > W::BITS {
+            let spilled = W::BITS - shift;
+            let next = &mut self.words[word_index + 1];
+            *next = (*next & !(mask >> spilled)) | (value >> spilled);
+        }
+    }
+}
+
+impl<'a, W: Word> From<BitSliceMut<'a, W>> for BitSlice<'a, W> {
+    fn from(slice: BitSliceMut<'a, W>) -> Self {
+        BitSlice {
+            words: slice.words,
+            offset: slice.offset,
+            len: slice.len,
+        }
+    }
+}
+
+impl<'a, W: Word> IntoIterator for BitSlice<'a, W> {
+    type Item = bool;
+    type IntoIter = Iter<'a, W>;
+
+    fn into_iter(self) -> Iter<'a, W> {
+        self.iter()
+    }
+}
+
+impl<W: Word> Iterator for Iter<'_, W> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.front += 1;
+        Some(self.slice.index(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<W: Word> DoubleEndedIterator for Iter<'_, W> {
+    fn next_back(&mut self) -> Option<bool> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(self.slice.index(self.back))
+    }
+}
+
+impl<W: Word> ExactSizeIterator for Iter<'_, W> {}
+
+impl<W: Word> Iterator for Runs<'_, W> {
+    type Item = (usize, usize, bool);
+
+    fn next(&mut self) -> Option<(usize, usize, bool)> {
+        let start = self.next;
+        let value = self.slice.try_index(start).ok()?;
+
+        self.next = self
+            .slice
+            .next_index_of(!value, start)
+            .unwrap_or(self.slice.len);
+
+        Some((start, self.next - 1, value))
+    }
+}
+
+/// converts any range into `(start, end_exclusive)`, failing if it doesn't fit into `len`
+pub(crate) fn try_resolve_range(
+    range: impl RangeBounds<usize>,
+    len: usize,
+) -> Result<(usize, usize), TightVecError> {
+    // a bound one past `usize::MAX` can't fit into any length
+    let past_max = TightVecError::RangeOutOfBounds {
+        end: usize::MAX,
+        len,
+    };
+
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).ok_or(past_max)?,
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).ok_or(past_max)?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if start > end {
+        return Err(TightVecError::InvertedRange { start, end });
+    }
+
+    if end > len {
+        return Err(TightVecError::RangeOutOfBounds { end, len });
+    }
+
+    Ok((start, end))
+}
+
+pub(crate) fn check_index(index: usize, len: usize) -> Result<(), TightVecError> {
+    if index >= len {
+        return Err(TightVecError::IndexOutOfBounds { index, len });
+    }
+
+    Ok(())
+}
+
+/// the storage words entirely covered by a view of `len` bits starting at `offset`
+fn full_word_range<W: Word>(offset: usize, len: usize) -> Range<usize> {
+    let start = offset.div_ceil(W::BITS);
+    let end = ((offset + len) / W::BITS).max(start);
+
+    start..end
+}
+
+/// the storage words of a view that aren't entirely covered by it: the first and the last one
+fn edge_word_indices<W: Word>(
+    offset: usize,
+    len: usize,
+    word_count: usize,
+) -> impl Iterator<Item = usize> {
+    let full = full_word_range::<W>(offset, len);
+    let last = word_count.checked_sub(1).filter(|&last| last != 0);
+
+    [Some(0).filter(|_| word_count > 0), last]
+        .into_iter()
+        .flatten()
+        .filter(move |word_index| !full.contains(word_index))
+}
+
+/// every storage word overlapping a view of `len` bits starting at `offset`, along with the mask
+/// of its bits inside the view
+#[cfg(feature = "std")]
+pub(crate) fn word_masks<W: Word>(offset: usize, len: usize) -> impl Iterator<Item = (usize, W)> {
+    (offset / W::BITS..(offset + len).div_ceil(W::BITS))
+        .map(move |word_index| (word_index, word_mask(word_index, offset, len)))
+}
+
+/// the bits of storage word `word_index` that belong to a view of `len` bits starting at `offset`
+fn word_mask<W: Word>(word_index: usize, offset: usize, len: usize) -> W {
+    let word_start = word_index * W::BITS;
+    let start = offset.saturating_sub(word_start);
+    let end = (offset + len - word_start).min(W::BITS);
+
+    W::low_mask(end) & !W::low_mask(start)
+}
+
+#[cfg(test)]
+mod test {
+    use core::ops::Bound;
+
+    use crate::test_util::pattern;
+    use crate::{TightVec, TightVecError};
+
+    #[test]
+    fn index_with_offset() {
+        let v: TightVec = pattern(300, 7);
+
+        for (start, end) in [(0, 300), (3, 70), (64, 128), (63, 65), (100, 299), (5, 5)] {
+            let s = v.slice(start..end);
+
+            assert_eq!(s.len(), end - start);
+            for i in 0..s.len() {
+                assert_eq!(s.index(i), v.index(start + i));
+            }
+            assert_eq!(
+                s.try_index(s.len()),
+                Err(TightVecError::IndexOutOfBounds {
+                    index: s.len(),
+                    len: s.len()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn sub_slice() {
+        let v: TightVec = pattern(300, 7);
+
+        let s = v.slice(10..=250).slice(60..130);
+
+        assert_eq!(s.len(), 70);
+        assert!(s.iter().eq((70..140).map(|i| v.index(i))));
+    }
+
+    #[test]
+    fn range_queries() {
+        let mut v: TightVec = TightVec::with_len_and_value(300, false);
+        v.fill_multiple(37, 201, true);
+
+        assert!(v.slice(37..=201).all());
+        assert!(!v.slice(36..=201).all());
+        assert!(!v.slice(37..=202).all());
+        assert!(v.slice(..).any());
+        assert!(!v.slice(202..).any());
+        assert!(!v.slice(0..37).any());
+        assert!(v.slice(0..0).all());
+        assert_eq!(v.slice(..).count_ones(), 165);
+        assert_eq!(v.slice(30..40).count_ones(), 3);
+        assert_eq!(v.slice(30..40).count_zeros(), 7);
+    }
+
+    #[test]
+    fn iter_both_ends() {
+        let v: TightVec = pattern(100, 7);
+        let s = v.slice(3..97);
+
+        let forward: Vec<bool> = s.iter().collect();
+        let mut backward: Vec<bool> = s.iter().rev().collect();
+        backward.reverse();
+
+        assert_eq!(forward, backward);
+        assert_eq!(s.iter().len(), 94);
+    }
+
+    #[test]
+    fn fill() {
+        let mut v: TightVec = TightVec::with_len_and_value(200, false);
+
+        v.slice_mut(5..150).fill(true);
+        v.slice_mut(70..80).fill(false);
+
+        for i in 0..200 {
+            assert_eq!(v.index(i), (5..150).contains(&i) && !(70..80).contains(&i));
+        }
+    }
+
+    #[test]
+    fn copy_between_offsets() {
+        let source: TightVec = pattern(300, 7);
+
+        for (from, to, len) in [
+            (0, 0, 300),
+            (3, 70, 200),
+            (64, 1, 150),
+            (17, 17, 64),
+            (0, 63, 1),
+        ] {
+            let mut v: TightVec = TightVec::with_len_and_value(300, true);
+
+            v.slice_mut(to..to + len)
+                .copy_from_bitslice(&source.slice(from..from + len));
+
+            for i in 0..300 {
+                let expected = if (to..to + len).contains(&i) {
+                    source.index(i - to + from)
+                } else {
+                    true
+                };
+                assert_eq!(v.index(i), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn to_tightvec() {
+        let v: TightVec = pattern(300, 7);
+
+        let copy = v.slice(13..213).to_tightvec();
+
+        assert_eq!(copy.len(), 200);
+        assert!((0..200).all(|i| copy.index(i) == v.index(i + 13)));
+    }
+
+    #[test]
+    fn searches() {
+        let v: TightVec = pattern(300, 7);
+        let s = v.slice(3..290);
+        let bits: Vec<bool> = s.iter().collect();
+
+        for value in [false, true] {
+            for from in [0, 1, 60, 61, 64, 128, 200, 286, 287] {
+                assert_eq!(
+                    s.next_index_of(value, from),
+                    (from..bits.len()).find(|&i| bits[i] == value)
+                );
+                assert_eq!(
+                    s.prev_index_of(value, from),
+                    (0..from.min(bits.len())).rev().find(|&i| bits[i] == value)
+                );
+            }
+        }
+
+        let ones: TightVec = TightVec::with_len_and_value(100, true);
+        assert_eq!(ones.slice(5..90).next_index_of(false, 0), None);
+        assert_eq!(ones.slice(5..90).prev_index_of(false, 85), None);
+    }
+
+    #[test]
+    fn runs() {
+        fn naive(bits: &[bool]) -> Vec<(usize, usize, bool)> {
+            let mut runs: Vec<(usize, usize, bool)> = Vec::new();
+            for (i, &value) in bits.iter().enumerate() {
+                match runs.last_mut() {
+                    Some(run) if run.2 == value => run.1 = i,
+                    _ => runs.push((i, i, value)),
+                }
+            }
+            runs
+        }
+
+        let v: TightVec = pattern(300, 7);
+        for (start, end) in [(0, 300), (3, 290), (64, 128), (70, 71), (5, 5)] {
+            let s = v.slice(start..end);
+            let bits: Vec<bool> = s.iter().collect();
+
+            assert!(s.runs().eq(naive(&bits)));
+        }
+
+        let mut v: TightVec<u8> = TightVec::with_len_and_value(40, true);
+        v.slice_mut(3..37).fill(false);
+        assert!(v.runs().eq([(0, 2, true), (3, 36, false), (37, 39, true)]));
+    }
+
+    #[test]
+    fn checked_ranges() {
+        let mut v: TightVec = pattern(100, 7);
+
+        assert_eq!(
+            v.try_slice(10..101).unwrap_err(),
+            TightVecError::RangeOutOfBounds { end: 101, len: 100 }
+        );
+        let (start, end) = (5, 3);
+        assert_eq!(
+            v.slice(10..20).try_slice(start..end).unwrap_err(),
+            TightVecError::InvertedRange { start: 5, end: 3 }
+        );
+
+        let mut s = v.slice_mut(10..20);
+        assert_eq!(
+            s.try_set(10, true),
+            Err(TightVecError::IndexOutOfBounds { index: 10, len: 10 })
+        );
+        assert!(s.try_slice_mut(..=10).is_err());
+
+        assert_eq!(
+            v.try_slice(..=usize::MAX).unwrap_err(),
+            TightVecError::RangeOutOfBounds {
+                end: usize::MAX,
+                len: 100
+            }
+        );
+        assert_eq!(
+            v.try_slice((Bound::Excluded(usize::MAX), Bound::Unbounded))
+                .unwrap_err(),
+            TightVecError::RangeOutOfBounds {
+                end: usize::MAX,
+                len: 100
+            }
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Fixtures shared by the test modules
+
+use crate::{BitGrid, TightVec, Word};
+
+/// an irregular mix of short runs, different for every seed
+pub(crate) fn pattern_bits(len: usize, seed: usize) -> Vec<bool> {
+    (0..len).map(|i| (i * seed + i / 3) % 5 < 2).collect()
+}
+
+/// [`pattern_bits`] as a vector
+pub(crate) fn pattern<W: Word>(len: usize, seed: usize) -> TightVec<W> {
+    from_bits(&pattern_bits(len, seed))
+}
+
+/// long runs of both values, for run-length encodings
+pub(crate) fn run_pattern<W: Word>(len: usize) -> TightVec<W> {
+    from_bits(
+        &(0..len)
+            .map(|i| (i / 5 + i / 17) % 3 == 0)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// a grid that changes from row to row, which [`pattern_bits`] cut into rows doesn't when the
+/// row length is a multiple of its period
+pub(crate) fn grid_pattern<W: Word>(rows: usize, cols: usize) -> BitGrid<W> {
+    let mut grid = BitGrid::new(rows, cols);
+    for row in 0..rows {
+        for col in 0..cols {
+            grid.set(row, col, (row * 7 + col * 3 + row * col) % 5 < 2);
+        }
+    }
+    grid
+}
+
+pub(crate) fn from_bits<W: Word>(bits: &[bool]) -> TightVec<W> {
+    let mut v = TightVec::default();
+    for &bit in bits {
+        v.push(bit);
+    }
+    v
+}
+
+/// a small deterministic generator, so failures are reproducible
+pub(crate) fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state
+    })
+}
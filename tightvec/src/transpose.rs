@@ -0,0 +1,173 @@
+use alloc::vec;
+
+use crate::error::or_panic;
+use crate::{BitGrid, BitSlice, StorageItem, TightVecError, Word};
+
+/// Column access to a [`BitGrid`] with the same word-level efficiency as rows, by keeping a
+/// transposed copy: column `c` of the grid is row `c` of the copy.
+///
+/// A snapshot; later changes to the grid aren't reflected.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitColumns<W: Word = StorageItem> {
+    transposed: BitGrid<W>,
+}
+
+impl<W: Word> BitGrid<W> {
+    /// Mirrors the grid along its main diagonal, so cell `(row, col)` ends up at `(col, row)`.
+    /// Works on blocks of `W::BITS` × `W::BITS` cells, `log2(W::BITS)` word operations per row
+    /// of a block.
+    pub fn transpose(&self) -> BitGrid<W> {
+        let mut transposed = BitGrid::new(self.cols(), self.rows());
+        let (words, words_per_row) = (self.get_raw(), self.words_per_row());
+        let transposed_words_per_row = transposed.words_per_row();
+        let out = transposed.words_mut();
+        let mut block = vec![W::ZERO; W::BITS];
+
+        for block_row in 0..self.rows().div_ceil(W::BITS) {
+            let first_row = block_row * W::BITS;
+            let block_rows = (self.rows() - first_row).min(W::BITS);
+
+            for block_col in 0..words_per_row {
+                // rows past the last one are read as empty
+                block.fill(W::ZERO);
+                for (i, word) in block[..block_rows].iter_mut().enumerate() {
+                    *word = words[(first_row + i) * words_per_row + block_col];
+                }
+
+                transpose_block(&mut block);
+
+                // rows of the block past the last column are padding, which is zero on both sides
+                let first_col = block_col * W::BITS;
+                let block_cols = (self.cols() - first_col).min(W::BITS);
+
+                for (i, &word) in block[..block_cols].iter().enumerate() {
+                    out[(first_col + i) * transposed_words_per_row + block_row] = word;
+                }
+            }
+        }
+
+        transposed
+    }
+
+    /// Column views of the grid, see [`BitColumns`]
+    pub fn columns(&self) -> BitColumns<W> {
+        BitColumns {
+            transposed: self.transpose(),
+        }
+    }
+}
+
+impl<W: Word> BitColumns<W> {
+    /// Number of rows of the original grid, i.e. the length of each column
+    pub fn rows(&self) -> usize {
+        self.transposed.cols()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.transposed.rows()
+    }
+
+    /// Read-only view of one column, top to bottom. Panics if the column is out of bounds.
+    #[track_caller]
+    pub fn column(&self, col: usize) -> BitSlice<'_, W> {
+        or_panic(self.try_column(col))
+    }
+
+    pub fn try_column(&self, col: usize) -> Result<BitSlice<'_, W>, TightVecError> {
+        self.transposed.try_row(col)
+    }
+
+    /// The transposed grid, for rectangle queries with rows and columns swapped
+    pub fn as_transposed(&self) -> &BitGrid<W> {
+        &self.transposed
+    }
+
+    pub fn into_transposed(self) -> BitGrid<W> {
+        self.transposed
+    }
+}
+
+/// transposes a square block of `W::BITS` words in place, bit `c` of word `r` being cell `(r, c)`.
+///
+/// Swaps the off-diagonal quadrants of ever smaller sub-blocks, all sub-blocks of one size at once
+/// (Hacker's Delight, section 7-3).
+fn transpose_block<W: Word>(block: &mut [W]) {
+    debug_assert_eq!(block.len(), W::BITS);
+
+    let mut width = W::BITS / 2;
+    // the left half of every sub-block of `2 * width` columns
+    let mut mask = W::low_mask(width);
+
+    while width > 0 {
+        for row in (0..W::BITS).filter(|row| row & width == 0) {
+            let swapped = ((block[row] >> width) ^ block[row + width]) & mask;
+
+            block[row] ^= swapped << width;
+            block[row + width] ^= swapped;
+        }
+
+        width /= 2;
+        mask ^= mask << width;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::grid_pattern;
+    use crate::{BitGrid, TightVecError, Word};
+
+    fn transposes<W: Word>() {
+        for (rows, cols) in [
+            (0, 0),
+            (1, 1),
+            (3, 200),
+            (200, 3),
+            (64, 64),
+            (130, 70),
+            (9, 129),
+        ] {
+            let grid = grid_pattern::<W>(rows, cols);
+            let transposed = grid.transpose();
+
+            assert_eq!((transposed.rows(), transposed.cols()), (cols, rows));
+            for row in 0..rows {
+                for col in 0..cols {
+                    assert_eq!(transposed.get(col, row), grid.get(row, col));
+                }
+            }
+
+            // also compares the padding words
+            assert_eq!(transposed.transpose(), grid);
+        }
+    }
+
+    #[test]
+    fn transpose() {
+        transposes::<u8>();
+        transposes::<u16>();
+        transposes::<u64>();
+        transposes::<u128>();
+    }
+
+    #[test]
+    fn columns() {
+        let grid: BitGrid = "..#..\n.###.\n#####\n.###.\n..#..".parse().unwrap();
+        let mut wide: BitGrid = BitGrid::new(3, 100);
+        wide.fill_rect(.., 70..80, true);
+        wide.set(1, 75, false);
+
+        let columns = grid.columns();
+        let wide_columns = wide.columns();
+
+        assert_eq!((columns.rows(), columns.cols()), (5, 5));
+        assert_eq!(columns.column(1).to_string(), "01110");
+        assert_eq!(columns.column(2).count_ones(), 5);
+        assert!(wide_columns.column(70).all());
+        assert!(!wide_columns.column(75).all());
+        assert!(wide_columns.as_transposed().all_set(76..80, ..));
+        assert_eq!(
+            columns.try_column(5).unwrap_err(),
+            TightVecError::IndexOutOfBounds { index: 5, len: 5 }
+        );
+    }
+}
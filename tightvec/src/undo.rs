@@ -0,0 +1,317 @@
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+use crate::error::or_panic;
+use crate::slice::{check_index, try_resolve_range};
+use crate::{BitContainer, BitContainerMut, StorageItem, TightVec, TightVecError, Word};
+
+/// A fixed-length bit vector that records the previous value of every word it changes, so it can
+/// be rolled back to a [`Checkpoint`] in time proportional to the changes made since.
+///
+/// Meant for backtracking searches: take a checkpoint before descending, mark entries as visited,
+/// and roll back when returning instead of cloning or clearing the whole set.
+#[derive(Clone, Debug, Default)]
+pub struct UndoTightVec<W: Word = StorageItem> {
+    bits: TightVec<W>,
+    /// `(word index, previous word, generation)`, oldest first
+    log: Vec<(usize, W, u64)>,
+    /// bumped by every logged change and by [`forget_history`](Self::forget_history), so a
+    /// position in the log that was rolled back and written again has a new generation
+    generation: u64,
+    /// generation of the empty log
+    base_generation: u64,
+}
+
+/// A state of an [`UndoTightVec`] to roll back to.
+///
+/// Rolling back to a checkpoint invalidates the checkpoints taken after it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// length of the log
+    len: usize,
+    /// generation of the newest change in the log
+    generation: u64,
+}
+
+impl<W: Word> UndoTightVec<W> {
+    /// Creates a vector of `len` unset entries
+    pub fn new(len: usize) -> Self {
+        Self::with_len_and_value(len, false)
+    }
+
+    pub fn with_len_and_value(len: usize, value: bool) -> Self {
+        TightVec::with_len_and_value(len, value).into()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn index(&self, index: usize) -> bool {
+        self.bits.index(index)
+    }
+
+    pub fn try_index(&self, index: usize) -> Result<bool, TightVecError> {
+        self.bits.try_index(index)
+    }
+
+    /// Panics if `index` is out of bounds
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: bool) {
+        or_panic(self.try_set(index, value))
+    }
+
+    pub fn try_set(&mut self, index: usize, value: bool) -> Result<(), TightVecError> {
+        check_index(index, self.len())?;
+
+        self.update(index / W::BITS, W::ONE << (index % W::BITS), value);
+
+        Ok(())
+    }
+
+    /// Sets every entry in the range to `value`. Panics if the range is out of bounds.
+    #[track_caller]
+    pub fn fill(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        or_panic(self.try_fill(range, value))
+    }
+
+    pub fn try_fill(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: bool,
+    ) -> Result<(), TightVecError> {
+        let (start, end) = try_resolve_range(range, self.len())?;
+
+        if start == end {
+            return Ok(());
+        }
+
+        for word_index in start / W::BITS..end.div_ceil(W::BITS) {
+            let word_start = word_index * W::BITS;
+            let mask = W::low_mask((end - word_start).min(W::BITS))
+                & !W::low_mask(start.saturating_sub(word_start));
+
+            self.update(word_index, mask, value);
+        }
+
+        Ok(())
+    }
+
+    /// The current state, to pass to [`rollback`](Self::rollback) later
+    pub fn checkpoint(&self) -> Checkpoint {
+        let len = self.log.len();
+
+        Checkpoint {
+            len,
+            generation: self.generation_at(len).unwrap(),
+        }
+    }
+
+    /// Undoes every change made since `checkpoint` was taken. Panics if the checkpoint was
+    /// invalidated by rolling back further or by [`forget_history`](Self::forget_history).
+    #[track_caller]
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        assert!(
+            self.generation_at(checkpoint.len) == Some(checkpoint.generation),
+            "checkpoint is no longer valid"
+        );
+
+        for (word_index, word, _) in self.log.drain(checkpoint.len..).rev() {
+            self.bits.inner[word_index] = word;
+        }
+    }
+
+    /// Number of word changes recorded, i.e. the cost of rolling back to the first checkpoint
+    pub fn recorded_changes(&self) -> usize {
+        self.log.len()
+    }
+
+    /// Keeps the current entries and drops the log. Invalidates every checkpoint.
+    pub fn forget_history(&mut self) {
+        self.log.clear();
+        self.generation += 1;
+        self.base_generation = self.generation;
+    }
+
+    pub fn as_tightvec(&self) -> &TightVec<W> {
+        &self.bits
+    }
+
+    /// The current entries, without the log
+    pub fn into_tightvec(self) -> TightVec<W> {
+        self.bits
+    }
+
+    /// sets or clears the bits of `mask` in one word, logging the word if it changes
+    fn update(&mut self, word_index: usize, mask: W, value: bool) {
+        let word = self.bits.inner[word_index];
+        let updated = if value { word | mask } else { word & !mask };
+
+        if updated != word {
+            self.generation += 1;
+            self.log.push((word_index, word, self.generation));
+            self.bits.inner[word_index] = updated;
+        }
+    }
+
+    /// the generation of the first `len` log entries, `None` if the log is shorter
+    fn generation_at(&self, len: usize) -> Option<u64> {
+        match len {
+            0 => Some(self.base_generation),
+            _ => self.log.get(len - 1).map(|&(_, _, generation)| generation),
+        }
+    }
+}
+
+impl<W: Word> From<TightVec<W>> for UndoTightVec<W> {
+    fn from(bits: TightVec<W>) -> Self {
+        Self {
+            bits,
+            log: Vec::new(),
+            generation: 0,
+            base_generation: 0,
+        }
+    }
+}
+
+impl<W: Word> From<UndoTightVec<W>> for TightVec<W> {
+    fn from(v: UndoTightVec<W>) -> Self {
+        v.into_tightvec()
+    }
+}
+
+impl<W: Word> BitContainer for UndoTightVec<W> {
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    #[track_caller]
+    fn get(&self, index: usize) -> bool {
+        self.index(index)
+    }
+
+    #[track_caller]
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> usize {
+        self.bits.count_ones_in(range)
+    }
+
+    fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter_ones()
+    }
+}
+
+impl<W: Word> BitContainerMut for UndoTightVec<W> {
+    #[track_caller]
+    fn set(&mut self, index: usize, value: bool) {
+        UndoTightVec::set(self, index, value)
+    }
+
+    #[track_caller]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        self.fill(range, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{TightVec, TightVecError, UndoTightVec};
+
+    #[test]
+    fn nested_rollback() {
+        let mut v: UndoTightVec<u8> = UndoTightVec::new(20);
+
+        v.set(1, true);
+        let outer = v.checkpoint();
+        v.fill(4..18, true);
+        let after_fill = v.as_tightvec().clone();
+
+        let inner = v.checkpoint();
+        v.set(1, false);
+        v.set(6, false);
+        v.fill(.., true);
+
+        v.rollback(inner);
+        assert_eq!(v.as_tightvec(), &after_fill);
+
+        v.rollback(outer);
+        assert_eq!(v.as_tightvec().to_string(), "01000000000000000000");
+        assert_eq!(v.recorded_changes(), 1);
+    }
+
+    #[test]
+    fn logs_only_changed_words() {
+        let mut v: UndoTightVec = UndoTightVec::with_len_and_value(200, false);
+
+        let start = v.checkpoint();
+        v.set(3, false);
+        v.fill(64..128, false);
+        assert_eq!(v.recorded_changes(), 0);
+
+        v.set(3, true);
+        v.set(4, true);
+        v.fill(60..130, true);
+        assert_eq!(v.recorded_changes(), 5);
+
+        v.rollback(start);
+        assert_eq!(v.into_tightvec(), TightVec::with_len_and_value(200, false));
+    }
+
+    #[test]
+    fn checked_access() {
+        let mut v: UndoTightVec = "10".parse::<TightVec>().unwrap().into();
+
+        assert_eq!(
+            v.try_set(2, true),
+            Err(TightVecError::IndexOutOfBounds { index: 2, len: 2 })
+        );
+        assert!(v.try_fill(1..3, true).is_err());
+        assert!(v.index(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "no longer valid")]
+    fn stale_checkpoint() {
+        let mut v: UndoTightVec = UndoTightVec::new(10);
+        let start = v.checkpoint();
+
+        v.set(1, true);
+        let later = v.checkpoint();
+        v.set(2, true);
+
+        v.rollback(start);
+        v.rollback(later);
+    }
+
+    #[test]
+    #[should_panic(expected = "no longer valid")]
+    fn checkpoint_of_regrown_log() {
+        let mut v: UndoTightVec = UndoTightVec::new(10);
+        let start = v.checkpoint();
+
+        v.set(1, true);
+        let later = v.checkpoint();
+        v.rollback(start);
+
+        // the log is as long as when `later` was taken, but holds other changes
+        v.set(2, true);
+        v.set(3, true);
+        v.rollback(later);
+    }
+
+    #[test]
+    #[should_panic(expected = "no longer valid")]
+    fn forgotten_checkpoint() {
+        let mut v: UndoTightVec = UndoTightVec::new(10);
+        let start = v.checkpoint();
+
+        v.forget_history();
+        v.set(1, true);
+        v.rollback(start);
+    }
+}
@@ -0,0 +1,136 @@
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr,
+    ShrAssign,
+};
+
+use crate::Endianness;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer usable as the backing storage of a [`TightVec`](crate::TightVec).
+///
+/// Implemented for `u8`, `u16`, `u32`, `u64` and `u128`. The trait is sealed, so the crate can rely on
+/// every word being a plain integer without padding.
+pub trait Word:
+    private::Sealed
+    + Copy
+    + Default
+    + Debug
+    + Eq
+    + Ord
+    + Hash
+    + Send
+    + Sync
+    + 'static
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+    + BitAndAssign
+    + BitOrAssign
+    + BitXorAssign
+    + ShlAssign<usize>
+    + ShrAssign<usize>
+{
+    /// Number of bits in one word
+    const BITS: usize;
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    fn count_ones(self) -> u32;
+
+    fn trailing_zeros(self) -> u32;
+
+    fn leading_zeros(self) -> u32;
+
+    fn reverse_bits(self) -> Self;
+
+    /// The lowest `min(BITS, 64)` bits of `value`
+    fn from_u64(value: u64) -> Self;
+
+    /// The lowest `min(BITS, 64)` bits of the word
+    fn to_u64(self) -> u64;
+
+    /// Appends the `BITS / 8` bytes of the word in the given byte order
+    fn write_bytes(self, endianness: Endianness, out: &mut Vec<u8>);
+
+    /// Reads a word from exactly `BITS / 8` bytes in the given byte order
+    fn read_bytes(bytes: &[u8], endianness: Endianness) -> Self;
+
+    /// The word with the lowest `bits` bits set
+    fn low_mask(bits: usize) -> Self {
+        if bits == 0 {
+            Self::ZERO
+        } else {
+            Self::MAX >> (Self::BITS - bits)
+        }
+    }
+
+    /// The word with every bit set to `value`
+    fn fill_value(value: bool) -> Self {
+        if value { Self::MAX } else { Self::ZERO }
+    }
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {$(
+        impl private::Sealed for $t {}
+
+        impl Word for $t {
+            const BITS: usize = <$t>::BITS as usize;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MAX: Self = <$t>::MAX;
+
+            fn count_ones(self) -> u32 {
+                <$t>::count_ones(self)
+            }
+
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+
+            fn leading_zeros(self) -> u32 {
+                <$t>::leading_zeros(self)
+            }
+
+            fn reverse_bits(self) -> Self {
+                <$t>::reverse_bits(self)
+            }
+
+            fn from_u64(value: u64) -> Self {
+                value as $t
+            }
+
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+
+            fn write_bytes(self, endianness: Endianness, out: &mut Vec<u8>) {
+                match endianness {
+                    Endianness::Little => out.extend_from_slice(&self.to_le_bytes()),
+                    Endianness::Big => out.extend_from_slice(&self.to_be_bytes()),
+                }
+            }
+
+            fn read_bytes(bytes: &[u8], endianness: Endianness) -> Self {
+                let bytes = bytes.try_into().expect("wrong number of bytes for word");
+
+                match endianness {
+                    Endianness::Little => <$t>::from_le_bytes(bytes),
+                    Endianness::Big => <$t>::from_be_bytes(bytes),
+                }
+            }
+        }
+    )*};
+}
+
+impl_word!(u8, u16, u32, u64, u128);